@@ -1,4 +1,4 @@
-use day::{error::Error, part1::process};
+use dayXX::{error::Error, part1::process};
 
 #[tracing::instrument]
 fn main() -> Result<(), Error> {
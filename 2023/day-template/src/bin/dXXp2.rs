@@ -1,4 +1,4 @@
-use day::{error::Error, part2::process};
+use dayXX::{error::Error, part2::process};
 
 #[tracing::instrument]
 fn main() -> Result<(), Error> {
@@ -8,7 +8,7 @@ use nom::{
     IResult,
 };
 
-fn parse_line(input: &str) -> IResult<&str, Line> {
+fn parse_line(input: &str) -> IResult<&str, Line<'_>> {
     let blankspace = map(consumed(many1(char('.'))), |(space_str, _spaces)| {
         Chunk::BlankSpace(space_str)
     });
@@ -21,7 +21,7 @@ fn parse_line(input: &str) -> IResult<&str, Line> {
 }
 
 /// Parse Input from AoC2023 Day 3 into Data Structure
-pub(crate) fn parse(input: &str) -> miette::Result<Engine, AocError> {
+pub(crate) fn parse(input: &str) -> miette::Result<Engine<'_>, AocError> {
     match separated_list1(line_ending, parse_line)(input.trim()) {
         Ok((remaining_input, engine)) => {
             if !remaining_input.trim().is_empty() {
@@ -73,9 +73,10 @@ impl TryFrom<&str> for Map {
     fn try_from(input: &str) -> Result<Self, Self::Error> {
         let mut tiles = HashMap::new();
         let mut starting_position: Option<Cell> = None;
+        let input = input.trim();
         let height = input.lines().count();
         let mut width: Option<usize> = None;
-        input.trim().lines().enumerate().for_each(|(y, line)| {
+        input.lines().enumerate().for_each(|(y, line)| {
             width.get_or_insert_with(|| line.chars().count());
             line.trim().chars().enumerate().for_each(|(x, c)| {
                 // We're counting "cells" not "points in space", start from (1, 1).
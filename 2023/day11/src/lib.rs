@@ -141,7 +141,7 @@ pub(crate) mod models {
         }
     }
     impl Universe {
-        pub(crate) fn measure(&self) -> Spacing {
+        pub(crate) fn measure(&self) -> Spacing<'_> {
             Spacing {
                 universe: self,
                 rows: (1..=self.height)
@@ -1,9 +1,12 @@
-#[cfg(test)]
 pub(crate) mod display;
 pub mod error;
 pub mod part1;
 pub mod part2;
 
+use crate::error::Error;
+use crate::models::Platform;
+use std::{collections::HashSet, iter};
+
 pub(crate) enum TiltDirection {
     North,
     East,
@@ -19,6 +22,40 @@ pub(crate) trait Spin {
     fn turbo(&mut self, iterations: usize) -> bool;
 }
 
+/// Every tilt of the spin cycle (as in part 2), until the platform ends up
+/// the same way it was after an earlier spin.
+pub fn frames(input: &str) -> Result<impl Iterator<Item = String>, Error> {
+    let mut platform: Platform = input.trim().try_into()?;
+    let initial = format!(
+        "Initial platform, load {}\n{platform}",
+        platform.total_load()
+    );
+    let mut seen = HashSet::from([platform.clone()]);
+    let (mut tilts, mut repeated) = (0usize, false);
+    let spins = iter::from_fn(move || {
+        if repeated {
+            return None;
+        }
+        let (direction, name) = match tilts % 4 {
+            0 => (TiltDirection::North, "north"),
+            1 => (TiltDirection::West, "west"),
+            2 => (TiltDirection::South, "south"),
+            _ => (TiltDirection::East, "east"),
+        };
+        platform.tilt(&direction);
+        tilts += 1;
+        if tilts % 4 == 0 {
+            repeated = !seen.insert(platform.clone());
+        }
+        let spin = tilts.div_ceil(4);
+        Some(format!(
+            "Spin {spin}, tilted {name}, load {}\n{platform}",
+            platform.total_load()
+        ))
+    });
+    Ok(iter::once(initial).chain(spins))
+}
+
 pub(crate) mod models {
     use crate::{error::Error, Spin, Tilt, TiltDirection};
     use fasthash::{spooky::Hash64, RandomState};
@@ -57,7 +57,7 @@ pub fn hash_sum(input: &str) -> u32 {
 /// model.
 ///
 /// [`Step`]: crate::models::Step
-pub fn parse(input: &str) -> Result<Step, Error> {
+pub fn parse(input: &str) -> Result<Step<'_>, Error> {
     common::nom_custom(parser::parse_step, input)
 }
 
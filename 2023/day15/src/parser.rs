@@ -31,7 +31,7 @@ use nom::{
 /// - `ot=7`
 ///
 /// [`Step`]: crate::models::Step
-pub fn parse_step(input: &str) -> IResult<&str, Step, Error> {
+pub fn parse_step(input: &str) -> IResult<&str, Step<'_>, Error> {
     map(
         tuple((alpha1, parse_instruction)),
         |(label, instruction)| Step { label, instruction },
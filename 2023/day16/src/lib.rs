@@ -1,5 +1,6 @@
 use crate::error::Error;
 use crate::models::Contraption;
+use std::iter;
 
 pub mod error;
 pub(crate) mod models;
@@ -18,6 +19,27 @@ const TEST_INPUT: &str = ".|...\\....
 .|....-|.\\
 ..//.|....";
 
+/// Every step of the beam travelling through the contraption from the
+/// top-left corner (as in part 1), until every beam has left or looped.
+pub fn frames(input: &str) -> Result<impl Iterator<Item = String>, Error> {
+    let mut contraption: Contraption = input.try_into()?;
+    let max_iterations = contraption.get_max_iterations();
+    let (mut step, mut finished) = (0, false);
+    Ok(iter::from_fn(move || {
+        if finished {
+            return None;
+        }
+        let frame = format!(
+            "Step {step}: {} tiles energized\n{contraption}",
+            contraption.num_energized_tiles()
+        );
+        finished = contraption.complete() || step == max_iterations;
+        contraption.step();
+        step += 1;
+        Some(frame)
+    }))
+}
+
 fn energize(contraption: &mut Contraption) -> Result<usize, Error> {
     for _ in 1..=contraption.get_max_iterations() {
         if contraption.complete() {
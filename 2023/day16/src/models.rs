@@ -1,7 +1,6 @@
 use crate::error::Error;
 use std::collections::BTreeMap;
 
-#[cfg(debug_assertions)]
 mod display;
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
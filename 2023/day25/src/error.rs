@@ -22,6 +22,7 @@ impl fmt::Display for Error {
         }
     }
 }
+impl std::error::Error for Error {}
 
 // If today's Nom parsers have scenarios where they need to throw custom errors,
 // then implement these on the Error enum:
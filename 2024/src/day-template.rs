@@ -1,19 +1,19 @@
 use aoc_common::{ParseError, RunnerError, Solution};
 use std::fmt::Display;
 
-pub struct Day {}
-impl Solution for Day {
-    type Parsed = &str;
+pub struct DayXX {}
+impl Solution for DayXX {
+    type Parsed = String;
 
     fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
-        todo!()
+        Ok(input.to_string())
     }
 
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
+    fn part1(&self, _input: &Self::Parsed) -> Result<impl Display, RunnerError> {
         Err::<usize, RunnerError>(RunnerError::Unimplemented)
     }
 
-    fn part2(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
+    fn part2(&self, _input: &Self::Parsed) -> Result<impl Display, RunnerError> {
         Err::<usize, RunnerError>(RunnerError::Unimplemented)
     }
 }
@@ -30,22 +30,11 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let solution = Day {};
+        let solution = DayXX {};
         let parsed = solution.parse(INPUT).unwrap();
         assert_eq!("", parsed);
     }
 
-    #[test]
-    fn test_part_one() {
-        let solution = Day {};
-        let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("", solution.part1(parsed).unwrap().to_string());
-    }
-
-    #[test]
-    fn test_part_two() {
-        let solution = Day {};
-        let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("", solution.part2(parsed).unwrap().to_string());
-    }
+    // Put each worked example from the puzzle in `examples/2024/XX/`.
+    aoc_common::example_tests!(DayXX {}, "../examples/2024/XX/*.txt");
 }
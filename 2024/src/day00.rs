@@ -1,6 +1,14 @@
-use aoc_common::{ParseError, RunnerError, Solution};
+use aoc_common::visualize::{Diagram, Frames, GraphFormat, Visualize};
+use aoc_common::{AocError, ParseError, RunnerError, Solution};
 
-use std::{collections::HashMap, fmt::Display};
+use std::{fmt::Display, iter};
+
+const BUTTON_PRESSES: u32 = 1000;
+/// How many button presses to watch for before giving up on finding cycles.
+const CYCLE_SEARCH_LIMIT: u32 = 100_000;
+/// How many times each input of the collector must send a high pulse before
+/// its cycle is trusted.
+const SIGHTINGS: usize = 3;
 
 /// This is a practice run for Advent starting tomorrow (originally: 2023, Day 20).
 pub struct Day00 {}
@@ -8,36 +16,13 @@ impl Solution for Day00 {
     type Parsed = models::Graph;
 
     fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
-        let mut modules = parser::get_modules(input.trim())?;
-
-        let connections: Vec<_> = modules
-            .iter()
-            .map(|(name, module)| {
-                (name.to_owned(), module.destinations().iter().map(String::to_owned).collect::<Vec<_>>())
-            })
-            .collect();
-
-        let mut conjunctions: HashMap<String, &mut models::ConjunctionModule> = modules
-            .iter_mut()
-            .filter_map(|(name, module_enum)| match module_enum {
-                models::Module::Conjunction(module_struct) => Some((name.to_owned(), module_struct)),
-                _ => None,
-            })
-            .collect();
-        connections.iter().for_each(|(name, destinations)| {
-            destinations.iter().for_each(|destination| {
-                if let Some(conjunction) = conjunctions.get_mut(destination.as_str()) {
-                    conjunction.state.entry(name.clone()).or_insert(models::Signal::Low);
-                }
-            });
-        });
-
-        Ok(modules)
+        parser::get_modules(input)
     }
 
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        let mut exec = models::Execution::new(input);
-        for _i in 0..1000 {
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        // Pushing the button changes the state of the modules, so simulate on a copy.
+        let mut exec = models::Execution::new(input.clone());
+        for _i in 0..BUTTON_PRESSES {
             exec.push_the_button();
             while !exec.is_queue_clear() {
                 exec.tick();
@@ -47,153 +32,377 @@ impl Solution for Day00 {
         Ok(exec)
     }
 
-    fn part2(&self, _input: Self::Parsed) -> Result<impl Display, RunnerError> {
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
         // Yeah so this is another one of Eric's "you can't bruteforce your
-        // way through this one, sucker" puzzles.
-        Err::<usize, RunnerError>(RunnerError::Unimplemented)
-        // let mut exec = models::Execution::new(input);
-        // while !exec.machine_on {
-        //     exec.push_the_button();
-        //     while !exec.is_queue_clear() {
-        //         exec.tick();
-        //     }
-        // }
-        // Ok(exec.presses)
+        // way through this one, sucker" puzzles. Instead, rely on the shape of
+        // the network: `rx` is fed by a single conjunction (the collector),
+        // whose inputs are each driven by a counter that sends a high pulse
+        // once every so many presses. The machine turns on once all of those
+        // counters line up, at the lowest common multiple of their cycles.
+        let collector = models::collector(input)?;
+        let inputs = match input.module(collector) {
+            models::Module::Conjunction(module) => module.inputs.len(),
+            _ => unreachable!("the collector is checked to be a conjunction"),
+        };
+
+        let mut exec = models::Execution::new(input.clone());
+        exec.watch(collector);
+        while exec.sightings().len() < inputs || exec.sightings().values().any(|presses| presses.len() < SIGHTINGS) {
+            if exec.presses == CYCLE_SEARCH_LIMIT {
+                return Err(RunnerError::Custom(format!(
+                    "Not every input of {} sent a high pulse {SIGHTINGS} times within {CYCLE_SEARCH_LIMIT} presses",
+                    input.name(collector)
+                )));
+            }
+            exec.push_the_button();
+            while !exec.is_queue_clear() {
+                exec.tick();
+            }
+            // Small networks might turn the machine on before every cycle has been seen.
+            if exec.machine_on {
+                return Ok(exec.presses as u64);
+            }
+        }
+
+        let mut sightings: Vec<_> = exec.sightings().iter().map(|(&id, presses)| (input.name(id), presses)).collect();
+        sightings.sort();
+        let mut cycles = sightings.into_iter().map(|(name, presses)| models::cycle(name, presses));
+        cycles.try_fold(1, |presses, cycle| Ok(lcm(presses, cycle? as u64)))
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    match b {
+        0 => a,
+        _ => gcd(b, a % b),
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+/// Steps through every pulse sent during part 1's button presses.
+impl Visualize for Day00 {
+    fn frames(&self, input: &str) -> Result<Frames, AocError> {
+        let mut exec = models::Execution::new(self.parse(input)?);
+        Ok(Box::new(iter::from_fn(move || {
+            if exec.is_queue_clear() {
+                if exec.presses == BUTTON_PRESSES {
+                    return None;
+                }
+                exec.push_the_button();
+            } else {
+                exec.tick();
+            }
+            Some(exec.frame())
+        })))
+    }
+}
+
+/// Draws the module network, with the state that every module starts in.
+impl Diagram for Day00 {
+    fn graph(&self, input: &str, format: GraphFormat) -> Result<String, AocError> {
+        let graph = self.parse(input)?;
+        Ok(match format {
+            GraphFormat::Dot => models::dot(&graph),
+            GraphFormat::Mermaid => models::mermaid(&graph),
+        })
     }
 }
 
 mod models {
-    use std::collections::HashMap;
+    use aoc_common::{ParseError, RunnerError};
+    use std::collections::{HashMap, VecDeque};
 
     pub type ModuleName = String;
-    pub type Graph = HashMap<ModuleName, Module>;
+    /// Modules are referred to by their index in the [`Graph`], rather than by
+    /// name, so that sending a pulse doesn't need to allocate or hash anything.
+    pub type ModuleId = u16;
+
+    /// Pretend that the button is a module too, so that every pulse has a source.
+    const BUTTON: ModuleId = ModuleId::MAX;
 
     trait Receiver {
-        fn process_pulse(&mut self, pulse: Pulse) -> Vec<Pulse>;
+        /// The signal to send on to every destination, if any.
+        fn process_pulse(&mut self, pulse: &Pulse) -> Option<Signal>;
     }
 
     #[derive(Debug, Clone)]
     pub struct BroadcastModule {
-        pub(crate) destinations: Vec<ModuleName>,
+        pub(crate) destinations: Vec<ModuleId>,
     }
     #[derive(Debug, Clone)]
     pub struct FlipFlopModule {
         pub(crate) on: bool,
-        pub(crate) destinations: Vec<ModuleName>,
+        pub(crate) destinations: Vec<ModuleId>,
     }
     #[derive(Debug, Clone)]
     pub struct ConjunctionModule {
-        pub(crate) state: HashMap<ModuleName, Signal>,
-        pub(crate) destinations: Vec<ModuleName>,
+        /// Every module that sends pulses to this one.
+        pub(crate) inputs: Vec<ModuleId>,
+        /// Whether the last pulse from each input was high, one bit per input.
+        pub(crate) memory: u64,
+        pub(crate) destinations: Vec<ModuleId>,
     }
     #[derive(Debug, Clone)]
     pub enum Module {
         Broadcast(BroadcastModule),
         FlipFlop(FlipFlopModule),
         Conjunction(ConjunctionModule),
+        /// A module that pulses get sent to without being described by the input (such as `rx`).
+        Output,
     }
 
-    #[derive(Debug, Clone, PartialEq, Eq)]
+    /// The kind of module that a line of the input describes.
+    #[derive(Debug, Clone, Copy)]
+    pub enum Kind {
+        Broadcast,
+        FlipFlop,
+        Conjunction,
+    }
+    /// A module as described by a line of the input, before any names are interned.
+    #[derive(Debug)]
+    pub struct Definition<'a> {
+        pub(crate) kind: Kind,
+        pub(crate) name: &'a str,
+        pub(crate) destinations: Vec<&'a str>,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Graph {
+        names: Vec<ModuleName>,
+        modules: Vec<Module>,
+        broadcaster: ModuleId,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
     pub enum Signal {
         High,
         Low,
     }
-    #[derive(Debug, Clone)]
+    #[derive(Debug, Clone, Copy)]
     pub struct Pulse {
-        source: ModuleName,
+        source: ModuleId,
         signal: Signal,
-        destination: ModuleName,
+        destination: ModuleId,
     }
     #[derive(Default)]
     struct PulsesCount {
         high: u32,
         low: u32,
     }
+    impl PulsesCount {
+        fn add(&mut self, signal: Signal, count: usize) {
+            match signal {
+                Signal::High => self.high += count as u32,
+                Signal::Low => self.low += count as u32,
+            }
+        }
+    }
     pub struct Execution {
         graph: Graph,
-        queue: Vec<Pulse>,
+        queue: VecDeque<Pulse>,
         total: PulsesCount,
+        rx: Option<ModuleId>,
         pub(crate) machine_on: bool,
         pub(crate) presses: u32,
+        /// The conjunction whose high inputs are being recorded, if any.
+        watching: Option<ModuleId>,
+        /// The presses during which each input of the watched conjunction sent it a high pulse.
+        sightings: HashMap<ModuleId, Vec<u32>>,
     }
 
     impl Receiver for BroadcastModule {
-        fn process_pulse(&mut self, pulse: Pulse) -> Vec<Pulse> {
-            self.destinations
-                .iter()
-                .map(|d| Pulse {
-                    source: pulse.destination.clone(),
-                    signal: pulse.signal.clone(),
-                    destination: d.to_owned(),
-                })
-                .collect()
+        fn process_pulse(&mut self, pulse: &Pulse) -> Option<Signal> {
+            Some(pulse.signal)
         }
     }
     impl Receiver for FlipFlopModule {
-        fn process_pulse(&mut self, pulse: Pulse) -> Vec<Pulse> {
-            if let Signal::Low = pulse.signal {
-                self.on = !self.on;
-                let new_signal = match self.on {
-                    true => Signal::High,
-                    false => Signal::Low,
-                };
-                self.destinations
-                    .iter()
-                    .map(|d| Pulse {
-                        source: pulse.destination.clone(),
-                        signal: new_signal.clone(),
-                        destination: d.to_owned(),
-                    })
-                    .collect()
-            } else {
-                vec![]
+        fn process_pulse(&mut self, pulse: &Pulse) -> Option<Signal> {
+            if let Signal::High = pulse.signal {
+                return None;
+            }
+            self.on = !self.on;
+            match self.on {
+                true => Some(Signal::High),
+                false => Some(Signal::Low),
             }
         }
     }
     impl Receiver for ConjunctionModule {
-        fn process_pulse(&mut self, pulse: Pulse) -> Vec<Pulse> {
-            *self.state.entry(pulse.source).or_insert(Signal::Low) = pulse.signal;
-            let new_signal = match self.state.values().all(|s| s == &Signal::High) {
-                true => Signal::Low,
-                false => Signal::High,
-            };
-            self.destinations
-                .iter()
-                .map(|d| Pulse {
-                    source: pulse.destination.clone(),
-                    signal: new_signal.clone(),
-                    destination: d.to_owned(),
-                })
-                .collect()
+        fn process_pulse(&mut self, pulse: &Pulse) -> Option<Signal> {
+            if let Some(input) = self.inputs.iter().position(|&input| input == pulse.source) {
+                match pulse.signal {
+                    Signal::High => self.memory |= 1 << input,
+                    Signal::Low => self.memory &= !(1 << input),
+                }
+            }
+            match self.memory.count_ones() as usize == self.inputs.len() {
+                true => Some(Signal::Low),
+                false => Some(Signal::High),
+            }
+        }
+    }
+    impl ConjunctionModule {
+        /// What the conjunction remembers of each of its inputs, in the same order.
+        fn remembered(&self) -> impl Iterator<Item = (ModuleId, Signal)> + '_ {
+            self.inputs.iter().enumerate().map(|(i, &input)| match self.memory & (1 << i) {
+                0 => (input, Signal::Low),
+                _ => (input, Signal::High),
+            })
         }
     }
     impl Module {
-        pub fn destinations(&self) -> &Vec<ModuleName> {
+        pub fn destinations(&self) -> &[ModuleId] {
             match self {
                 Self::Broadcast(module) => &module.destinations,
                 Self::FlipFlop(module) => &module.destinations,
                 Self::Conjunction(module) => &module.destinations,
+                Self::Output => &[],
+            }
+        }
+
+        /// The prefix that the module's name has in the input.
+        fn kind(&self) -> char {
+            match self {
+                Self::Broadcast(_) | Self::Output => ' ',
+                Self::FlipFlop(_) => '%',
+                Self::Conjunction(_) => '&',
+            }
+        }
+    }
+
+    impl Graph {
+        /// Intern the name of every module (including those that only appear as
+        /// a destination), and work out which inputs each conjunction has.
+        pub(crate) fn new(definitions: Vec<Definition<'_>>) -> Result<Self, ParseError> {
+            let mut ids: HashMap<&str, ModuleId> = HashMap::new();
+            let mut names: Vec<ModuleName> = Vec::new();
+            let defined = definitions.iter().map(|definition| definition.name);
+            for name in defined.chain(definitions.iter().flat_map(|definition| definition.destinations.iter().copied()))
+            {
+                if !ids.contains_key(name) {
+                    let id = ModuleId::try_from(names.len())
+                        .ok()
+                        .filter(|&id| id != BUTTON)
+                        .ok_or_else(|| ParseError::Custom(format!("Expected at most {BUTTON} modules")))?;
+                    ids.insert(name, id);
+                    names.push(name.to_string());
+                }
+            }
+
+            let mut modules = vec![Module::Output; names.len()];
+            for definition in &definitions {
+                let id = ids[definition.name] as usize;
+                if !matches!(modules[id], Module::Output) {
+                    return Err(ParseError::Custom(format!("Module {} is defined more than once", definition.name)));
+                }
+                let destinations = definition.destinations.iter().map(|name| ids[name]).collect();
+                modules[id] = match definition.kind {
+                    Kind::Broadcast => Module::Broadcast(BroadcastModule { destinations }),
+                    Kind::FlipFlop => Module::FlipFlop(FlipFlopModule { on: false, destinations }),
+                    Kind::Conjunction => {
+                        Module::Conjunction(ConjunctionModule { inputs: Vec::new(), memory: 0, destinations })
+                    },
+                };
+            }
+            for source in 0..modules.len() {
+                for destination in modules[source].destinations().to_vec() {
+                    if let Module::Conjunction(conjunction) = &mut modules[destination as usize] {
+                        if !conjunction.inputs.contains(&(source as ModuleId)) {
+                            conjunction.inputs.push(source as ModuleId);
+                        }
+                    }
+                }
+            }
+            if let Some(id) = modules.iter().position(|module| match module {
+                Module::Conjunction(conjunction) => conjunction.inputs.len() > u64::BITS as usize,
+                _ => false,
+            }) {
+                let message = format!("Expected conjunction {} to have at most {} inputs", names[id], u64::BITS);
+                return Err(ParseError::Custom(message));
+            }
+
+            let broadcaster = match ids.get("broadcaster") {
+                Some(&id) if matches!(modules[id as usize], Module::Broadcast(_)) => id,
+                _ => return Err(ParseError::Custom("Expected a broadcaster module".to_string())),
+            };
+            Ok(Self { names, modules, broadcaster })
+        }
+
+        pub(crate) fn id(&self, name: &str) -> Option<ModuleId> {
+            self.names.iter().position(|candidate| candidate == name).map(|id| id as ModuleId)
+        }
+
+        pub(crate) fn name(&self, id: ModuleId) -> &str {
+            match id {
+                BUTTON => "button",
+                _ => &self.names[id as usize],
+            }
+        }
+
+        pub(crate) fn module(&self, id: ModuleId) -> &Module {
+            &self.modules[id as usize]
+        }
+
+        /// Every module, including those that only appear as a destination, in order of their names.
+        fn sorted(&self) -> Vec<(ModuleId, &Module)> {
+            let mut modules: Vec<_> = (0..self.modules.len() as ModuleId).map(|id| (id, self.module(id))).collect();
+            modules.sort_by_key(|(id, _)| self.name(*id));
+            modules
+        }
+
+        /// Whether a flip-flop is on, or what a conjunction remembers of each of its inputs.
+        fn state(&self, module: &Module) -> String {
+            match module {
+                Module::Broadcast(_) | Module::Output => String::new(),
+                Module::FlipFlop(module) => if module.on { "on" } else { "off" }.to_string(),
+                Module::Conjunction(module) => {
+                    let mut inputs: Vec<_> =
+                        module.remembered().map(|(input, signal)| (self.name(input), signal)).collect();
+                    inputs.sort();
+                    inputs.iter().map(|(name, signal)| format!("{name}:{signal}")).collect::<Vec<_>>().join(" ")
+                },
             }
         }
+
+        fn destination_names(&self, module: &Module) -> Vec<&str> {
+            module.destinations().iter().map(|&id| self.name(id)).collect()
+        }
     }
 
     impl Execution {
         pub fn new(graph: Graph) -> Self {
             Self {
+                rx: graph.id("rx"),
                 graph,
-                queue: Vec::new(),
+                queue: VecDeque::new(),
                 total: PulsesCount::default(),
                 machine_on: false,
                 presses: 0,
+                watching: None,
+                sightings: HashMap::new(),
             }
         }
 
+        /// Start recording which presses each input of a conjunction sends it a high pulse in.
+        pub(crate) fn watch(&mut self, conjunction: ModuleId) {
+            self.watching = Some(conjunction);
+            self.sightings.clear();
+        }
+
+        pub(crate) fn sightings(&self) -> &HashMap<ModuleId, Vec<u32>> {
+            &self.sightings
+        }
+
         pub(crate) fn push_the_button(&mut self) {
             self.presses += 1;
-            self.fire(Pulse {
-                source: "button".to_string(),
+            self.total.add(Signal::Low, 1);
+            self.queue.push_back(Pulse {
+                source: BUTTON,
                 signal: Signal::Low,
-                destination: "broadcaster".to_string(),
+                destination: self.graph.broadcaster,
             });
         }
 
@@ -201,29 +410,182 @@ mod models {
             self.queue.is_empty()
         }
 
-        fn fire(&mut self, pulse: Pulse) {
-            match &pulse.signal {
-                Signal::High => self.total.high += 1,
-                Signal::Low => self.total.low += 1,
+        pub(crate) fn tick(&mut self) {
+            let Some(pulse) = self.queue.pop_front() else {
+                return;
             };
-            self.queue.push(pulse);
+            if self.rx == Some(pulse.destination) && pulse.signal == Signal::Low {
+                self.machine_on = true;
+            }
+            if pulse.signal == Signal::High && self.watching == Some(pulse.destination) {
+                let presses = self.sightings.entry(pulse.source).or_default();
+                // Only the first high pulse of each press counts.
+                if presses.last() != Some(&self.presses) {
+                    presses.push(self.presses);
+                }
+            }
+            let module = &mut self.graph.modules[pulse.destination as usize];
+            let signal = match module {
+                Module::Broadcast(module) => module.process_pulse(&pulse),
+                Module::FlipFlop(module) => module.process_pulse(&pulse),
+                Module::Conjunction(module) => module.process_pulse(&pulse),
+                Module::Output => None,
+            };
+            if let Some(signal) = signal {
+                let destinations = module.destinations();
+                self.total.add(signal, destinations.len());
+                self.queue.extend(destinations.iter().map(|&destination| Pulse {
+                    source: pulse.destination,
+                    signal,
+                    destination,
+                }));
+            }
         }
+    }
 
-        pub(crate) fn tick(&mut self) {
-            self.queue.rotate_left(1);
-            if let Some(pulse) = self.queue.pop() {
-                if &pulse.destination == "rx" && pulse.signal == Signal::Low {
-                    self.machine_on = true;
-                }
-                if let Some(module) = self.graph.get_mut(&pulse.destination) {
-                    match module {
-                        Module::FlipFlop(module) => module.process_pulse(pulse).into_iter().for_each(|p| self.fire(p)),
-                        Module::Conjunction(module) => {
-                            module.process_pulse(pulse).into_iter().for_each(|p| self.fire(p))
-                        },
-                        Module::Broadcast(module) => module.process_pulse(pulse).into_iter().for_each(|p| self.fire(p)),
-                    };
-                }
+    /// Find the conjunction that feeds `rx`, checking that the network has the
+    /// shape that part 2's cycle analysis relies on.
+    pub(crate) fn collector(graph: &Graph) -> Result<ModuleId, RunnerError> {
+        let feeding: Vec<ModuleId> = match graph.id("rx") {
+            Some(rx) => graph
+                .sorted()
+                .into_iter()
+                .filter(|(_, module)| module.destinations().contains(&rx))
+                .map(|(id, _)| id)
+                .collect(),
+            None => Vec::new(),
+        };
+        let id = match feeding[..] {
+            [id] => id,
+            _ => {
+                return Err(RunnerError::Custom(format!(
+                    "Expected exactly one module to send pulses to rx, but found {}",
+                    feeding.len()
+                )))
+            },
+        };
+        let name = graph.name(id);
+        match graph.module(id) {
+            Module::Conjunction(module) if !module.inputs.is_empty() => Ok(id),
+            Module::Conjunction(_) => Err(RunnerError::Custom(format!("Conjunction {name} has no inputs"))),
+            _ => Err(RunnerError::Custom(format!("Expected {name}, which sends pulses to rx, to be a conjunction"))),
+        }
+    }
+
+    /// The number of presses between the high pulses an input sent, checking
+    /// that they are evenly spaced and start from the very first press (so
+    /// that the cycles of every input can be combined).
+    pub(crate) fn cycle(name: &str, presses: &[u32]) -> Result<u32, RunnerError> {
+        let cycle = presses[0];
+        let regular = presses.iter().enumerate().all(|(i, &press)| press == cycle * (i as u32 + 1));
+        match regular {
+            true => Ok(cycle),
+            false => Err(RunnerError::Custom(format!(
+                "Expected {name} to send a high pulse once every {cycle} presses, but it did during presses {presses:?}"
+            ))),
+        }
+    }
+
+    impl Execution {
+        /// The state of every module, and the pulse that gets delivered next.
+        pub(crate) fn frame(&self) -> String {
+            let mut frame = format!(
+                "Button presses: {}, pulses sent: {} low, {} high\n",
+                self.presses, self.total.low, self.total.high
+            );
+            let next = self.queue.front();
+            let status = match next {
+                Some(pulse) => format!(
+                    "Next pulse: {} -{}-> {}\n",
+                    self.graph.name(pulse.source),
+                    pulse.signal,
+                    self.graph.name(pulse.destination)
+                ),
+                None => "Waiting for the button to be pushed\n".to_string(),
+            };
+            frame.push_str(&status);
+            let modules: Vec<_> =
+                self.graph.sorted().into_iter().filter(|(_, module)| !matches!(module, Module::Output)).collect();
+            let width = modules.iter().map(|(id, _)| self.graph.name(*id).len()).max().unwrap_or_default() + 1;
+            for (id, module) in modules {
+                let (name, kind, state) = (self.graph.name(id), module.kind(), self.graph.state(module));
+                let marker = match next.is_some_and(|pulse| pulse.destination == id) {
+                    true => '▶',
+                    false => ' ',
+                };
+                let destinations = self.graph.destination_names(module).join(", ");
+                let line = format!("{marker} {kind}{name:<width$} -> {destinations:<24} {state}");
+                frame.push_str(line.trim_end());
+                frame.push('\n');
+            }
+            frame
+        }
+    }
+
+    /// Draw the network in Graphviz's DOT language, labelling each module with
+    /// its state. IDs are quoted, as module names can be keywords like `node`.
+    pub(crate) fn dot(graph: &Graph) -> String {
+        let mut lines = vec![
+            "digraph modules {".to_string(),
+            "    rankdir=LR;".to_string(),
+            "    node [fontname=\"monospace\"];".to_string(),
+        ];
+        let modules = graph.sorted();
+        for (id, module) in &modules {
+            let name = graph.name(*id);
+            let shape = match module {
+                Module::Broadcast(_) => "doublecircle",
+                Module::FlipFlop(_) => "box",
+                Module::Conjunction(_) => "diamond",
+                Module::Output => "plaintext",
+            };
+            let label = match module {
+                Module::FlipFlop(_) | Module::Conjunction(_) => {
+                    format!("{}{name}\\n{}", module.kind(), graph.state(module))
+                },
+                _ => name.to_string(),
+            };
+            lines.push(format!("    \"{name}\" [shape={shape}, label=\"{label}\"];"));
+        }
+        for (id, module) in &modules {
+            for destination in graph.destination_names(module) {
+                lines.push(format!("    \"{}\" -> \"{destination}\";", graph.name(*id)));
+            }
+        }
+        lines.push("}\n".to_string());
+        lines.join("\n")
+    }
+
+    /// Draw the network as a Mermaid flowchart, labelling each module with its
+    /// state in the same way as [`dot`]. Nodes get generated IDs, as some names
+    /// (like `end`) would break the flowchart.
+    pub(crate) fn mermaid(graph: &Graph) -> String {
+        let mut lines = vec!["flowchart LR".to_string()];
+        let modules = graph.sorted();
+        for (id, module) in &modules {
+            let name = graph.name(*id);
+            let node = match module {
+                Module::Broadcast(_) => format!("((\"{name}\"))"),
+                Module::FlipFlop(_) => format!("[\"%{name}<br/>{}\"]", graph.state(module)),
+                Module::Conjunction(_) => format!("{{\"&{name}<br/>{}\"}}", graph.state(module)),
+                Module::Output => format!(">\"{name}\"]"),
+            };
+            lines.push(format!("    m{id}{node}"));
+        }
+        for (id, module) in &modules {
+            for destination in module.destinations() {
+                lines.push(format!("    m{id} --> m{destination}"));
+            }
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    impl ::std::fmt::Display for Signal {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+            match self {
+                Self::High => write!(f, "high"),
+                Self::Low => write!(f, "low"),
             }
         }
     }
@@ -235,92 +597,167 @@ mod models {
 }
 
 mod parser {
-    use std::collections::HashMap;
-
     use super::models::*;
     use aoc_common::ParseError;
     use nom::{
         branch::alt,
         bytes::complete::tag,
         character::complete::{alphanumeric1, newline, space0, space1},
-        combinator::map,
+        combinator::{all_consuming, cut, map},
+        error::{context, VerboseError},
         multi::{separated_list0, separated_list1},
-        sequence::tuple,
+        sequence::{preceded, tuple},
         IResult,
     };
 
-    fn parse_destinations(input: &str) -> IResult<&str, Vec<ModuleName>> {
-        map(
-            tuple((
-                space1::<&str, ::nom::error::Error<&str>>,
-                tag("->"),
-                space1,
-                separated_list1(tuple((tag(","), space0)), alphanumeric1),
-            )),
-            |(_, _, _, names)| names.iter().map(|s| s.to_string()).collect(),
+    type PResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+    fn parse_destinations(input: &str) -> PResult<'_, Vec<&str>> {
+        context(
+            "a list of destinations",
+            preceded(tuple((space1, tag("->"), space1)), separated_list1(tuple((tag(","), space0)), alphanumeric1)),
         )(input)
     }
 
-    fn parse_broadcast(input: &str) -> IResult<&str, (ModuleName, Module)> {
-        map(tuple((tag("broadcaster"), parse_destinations)), |(_, destinations)| {
-            ("broadcaster".to_string(), Module::Broadcast(BroadcastModule { destinations }))
+    // Once the prefix of a module has matched, it can't be any other kind of
+    // module, so don't let `alt` backtrack and hide where it went wrong.
+    fn parse_broadcast(input: &str) -> PResult<'_, Definition<'_>> {
+        map(tuple((tag("broadcaster"), cut(parse_destinations))), |(name, destinations)| Definition {
+            kind: Kind::Broadcast,
+            name,
+            destinations,
         })(input)
     }
 
-    fn parse_flipflop(input: &str) -> IResult<&str, (ModuleName, Module)> {
-        map(tuple((tag("%"), alphanumeric1, parse_destinations)), |(_, name, destinations)| {
-            (name.to_string(), Module::FlipFlop(FlipFlopModule { on: false, destinations }))
+    fn parse_flipflop(input: &str) -> PResult<'_, Definition<'_>> {
+        map(preceded(tag("%"), cut(tuple((alphanumeric1, parse_destinations)))), |(name, destinations)| Definition {
+            kind: Kind::FlipFlop,
+            name,
+            destinations,
         })(input)
     }
 
-    fn parse_conjunction(input: &str) -> IResult<&str, (ModuleName, Module)> {
-        map(tuple((tag("&"), alphanumeric1, parse_destinations)), |(_, name, destinations)| {
-            (name.to_string(), Module::Conjunction(ConjunctionModule { state: HashMap::default(), destinations }))
+    fn parse_conjunction(input: &str) -> PResult<'_, Definition<'_>> {
+        map(preceded(tag("&"), cut(tuple((alphanumeric1, parse_destinations)))), |(name, destinations)| Definition {
+            kind: Kind::Conjunction,
+            name,
+            destinations,
         })(input)
     }
 
-    fn parse_module(input: &str) -> IResult<&str, (ModuleName, Module)> {
-        alt((parse_broadcast, parse_flipflop, parse_conjunction))(input)
+    fn parse_module(input: &str) -> PResult<'_, Definition<'_>> {
+        context("a module", alt((parse_broadcast, parse_flipflop, parse_conjunction)))(input)
     }
 
-    fn parse_lines(input: &str) -> IResult<&str, Vec<(ModuleName, Module)>> {
+    fn parse_lines(input: &str) -> PResult<'_, Vec<Definition<'_>>> {
         separated_list0(newline, parse_module)(input)
     }
 
     pub(crate) fn get_modules(input: &str) -> Result<Graph, ParseError> {
-        let (_, modules) = parse_lines(input.trim()).map_err(|err| ParseError::Nom(err.to_string()))?;
-        let modules: Graph = modules.into_iter().collect();
-        Ok(modules)
+        let input = input.trim();
+        let (_, definitions) = all_consuming(parse_lines)(input).map_err(|err| crate::nom::parse_error(input, err))?;
+        Graph::new(definitions)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::rstest;
+
+    aoc_common::example_tests!(Day00 {}, "../examples/2024/00/*.txt");
 
     #[test]
-    fn test_first_example() {
-        let input = "broadcaster -> a, b, c
-%a -> b
-%b -> c
-%c -> inv
-&inv -> a";
-        let solution = Day00 {};
-        let graph = solution.parse(input).unwrap();
-        let exec = solution.part1(&graph).unwrap();
-        assert_eq!("32000000".to_string(), exec.to_string());
+    fn test_frames() {
+        let frames: Vec<String> = Day00 {}.frames("broadcaster -> a\n%a -> inv\n&inv -> a").unwrap().take(4).collect();
+        assert!(frames[0]
+            .starts_with("Button presses: 1, pulses sent: 1 low, 0 high\nNext pulse: button -low-> broadcaster\n"));
+        assert!(frames[2].contains("▶ &inv "));
+        assert!(frames[3].lines().any(|line| line.starts_with("  &inv ") && line.ends_with(" a:high")));
+    }
+
+    #[test]
+    fn test_graph() {
+        let input = "broadcaster -> a\n%a -> inv, rx\n&inv -> a";
+        let dot = Day00 {}.graph(input, GraphFormat::Dot).unwrap();
+        assert_eq!(
+            [
+                "digraph modules {",
+                "    rankdir=LR;",
+                "    node [fontname=\"monospace\"];",
+                "    \"a\" [shape=box, label=\"%a\\noff\"];",
+                "    \"broadcaster\" [shape=doublecircle, label=\"broadcaster\"];",
+                "    \"inv\" [shape=diamond, label=\"&inv\\na:low\"];",
+                "    \"rx\" [shape=plaintext, label=\"rx\"];",
+                "    \"a\" -> \"inv\";",
+                "    \"a\" -> \"rx\";",
+                "    \"broadcaster\" -> \"a\";",
+                "    \"inv\" -> \"a\";",
+                "}",
+            ],
+            dot.lines().collect::<Vec<_>>()[..]
+        );
+        let mermaid = Day00 {}.graph(input, GraphFormat::Mermaid).unwrap();
+        assert_eq!(
+            [
+                "flowchart LR",
+                "    m1[\"%a<br/>off\"]",
+                "    m0((\"broadcaster\"))",
+                "    m2{\"&inv<br/>a:low\"}",
+                "    m3>\"rx\"]",
+                "    m1 --> m2",
+                "    m1 --> m3",
+                "    m0 --> m1",
+                "    m2 --> m1",
+            ],
+            mermaid.lines().collect::<Vec<_>>()[..]
+        );
+    }
+
+    #[test]
+    fn test_graph_keywords() {
+        let input = "broadcaster -> node, end\n%node -> end\n&end -> graph";
+        let dot = Day00 {}.graph(input, GraphFormat::Dot).unwrap();
+        assert!(dot.contains("    \"node\" -> \"end\";\n"));
+        assert!(dot.contains("    \"end\" -> \"graph\";\n"));
+        let mermaid = Day00 {}.graph(input, GraphFormat::Mermaid).unwrap();
+        assert!(mermaid.lines().skip(1).all(|line| line.trim_start().starts_with('m')), "{mermaid}");
+        assert!(mermaid.contains("{\"&end<br/>broadcaster:low node:low\"}"));
+    }
+
+    #[rstest]
+    #[case("%a -> b\n&b -> a", "Expected a broadcaster module")]
+    #[case("broadcaster -> a\n%a -> b\n&a -> b", "Module a is defined more than once")]
+    fn test_parse_invalid_network(#[case] input: &str, #[case] message: &str) {
+        assert_eq!(message, Day00 {}.parse(input).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_parse_syntax_error() {
+        let err = Day00 {}.parse("broadcaster -> a\n%a -> , b").unwrap_err();
+        assert_eq!(
+            "Expected AlphaNumeric in a list of destinations in a module at line 2, column 7:\n2 | %a -> , b\n  |       ^",
+            err.to_string()
+        );
+    }
+
+    #[rstest]
+    #[case("broadcaster -> a\n%a -> b\n&b -> a", "Expected exactly one module to send pulses to rx, but found 0")]
+    #[case("broadcaster -> a, b\n%a -> rx\n%b -> rx", "Expected exactly one module to send pulses to rx, but found 2")]
+    #[case("broadcaster -> a\n%a -> rx", "Expected a, which sends pulses to rx, to be a conjunction")]
+    fn test_part2_unexpected_network(#[case] input: &str, #[case] message: &str) {
+        let parsed = Day00 {}.parse(input).unwrap();
+        let err = Day00 {}.part2(&parsed).map(|answer| answer.to_string()).unwrap_err();
+        assert_eq!(message, err.to_string());
     }
 
     #[test]
-    fn test_second_example() {
-        let input = "broadcaster -> a
-%a -> inv, con
-&inv -> b
-%b -> con
-&con -> output";
-        let solution = Day00 {};
-        let graph = solution.parse(input).unwrap();
-        let exec = solution.part1(&graph).unwrap();
-        assert_eq!("11687500".to_string(), exec.to_string())
+    fn test_cycle() {
+        assert_eq!(Ok(4), models::cycle("a", &[4, 8, 12]).map_err(|err| err.to_string()));
+        let err = models::cycle("a", &[1, 3, 5]).unwrap_err();
+        assert_eq!(
+            "Expected a to send a high pulse once every 1 presses, but it did during presses [1, 3, 5]",
+            err.to_string()
+        );
     }
 }
@@ -20,14 +20,15 @@ impl Solution for Day01 {
         Ok((first, second))
     }
 
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        let (mut first, mut second) = input;
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        // Sorting mutates the lists, so this part has to work on its own copy.
+        let (mut first, mut second) = input.clone();
         first.sort();
         second.sort();
         Ok(first.iter().zip(second).map(|(a, b)| (a - b).abs()).sum::<isize>())
     }
 
-    fn part2(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
         let (first, second) = input;
         // Iterate just once through the second list to calculate ahead of time
         // how many times each number appears, instead of performing the count
@@ -37,7 +38,7 @@ impl Solution for Day01 {
             *carry.entry(*item).or_insert(0) += 1;
             carry
         });
-        Ok(first.into_iter().map(|a| a * counts.get(&a).unwrap_or(&0)).sum::<isize>())
+        Ok(first.iter().map(|a| a * counts.get(a).unwrap_or(&0)).sum::<isize>())
         // Note: I don't think there's much benefit due to the overhead of an
         // additional data structure; maybe better with bigger lists and more
         // repeating numbers. The following works just as well:
@@ -63,17 +64,5 @@ mod tests {
         assert_eq!((vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3]), parsed);
     }
 
-    #[test]
-    fn test_part1_example() {
-        let solution = Day01 {};
-        let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("11".to_string(), solution.part1(parsed).unwrap().to_string());
-    }
-
-    #[test]
-    fn test_part2_example() {
-        let solution = Day01 {};
-        let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("31".to_string(), solution.part2(parsed).unwrap().to_string());
-    }
+    aoc_common::example_tests!(Day01 {}, "../examples/2024/01/*.txt");
 }
@@ -20,7 +20,7 @@ impl Solution for Day02 {
             .map_err(ParseError::Int)
     }
 
-    fn part1(&self, reports: Self::Parsed) -> Result<impl Display, RunnerError> {
+    fn part1(&self, reports: &Self::Parsed) -> Result<impl Display, RunnerError> {
         Ok(reports
             .iter()
             .filter_map(|levels| get_tolerant_report(levels, false).ok())
@@ -28,7 +28,7 @@ impl Solution for Day02 {
             .count())
     }
 
-    fn part2(&self, reports: Self::Parsed) -> Result<impl Display, RunnerError> {
+    fn part2(&self, reports: &Self::Parsed) -> Result<impl Display, RunnerError> {
         Ok(reports
             .iter()
             .filter_map(|levels| {
@@ -107,12 +107,7 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_part_one() {
-        let solution = Day02 {};
-        let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("2", solution.part1(parsed).unwrap().to_string());
-    }
+    aoc_common::example_tests!(Day02 {}, "../examples/2024/02/*.txt");
 
     #[rstest]
     #[case("1 2 3 4 5", true)]
@@ -124,14 +119,7 @@ mod tests {
     fn test_part_one_combinations(#[case] report: &str, #[case] valid: bool) {
         let solution = Day02 {};
         let parsed = solution.parse(report).unwrap();
-        assert_eq!(if valid { "1" } else { "0" }, solution.part1(parsed).unwrap().to_string());
-    }
-
-    #[test]
-    fn test_part_two() {
-        let solution = Day02 {};
-        let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("4", solution.part2(parsed).unwrap().to_string());
+        assert_eq!(if valid { "1" } else { "0" }, solution.part1(&parsed).unwrap().to_string());
     }
 
     #[rstest]
@@ -148,7 +136,7 @@ mod tests {
     fn test_part_two_combinations(#[case] report: &str, #[case] valid: bool) {
         let solution = Day02 {};
         let parsed = solution.parse(report).unwrap();
-        assert_eq!(if valid { "1" } else { "0" }, solution.part2(parsed).unwrap().to_string());
+        assert_eq!(if valid { "1" } else { "0" }, solution.part2(&parsed).unwrap().to_string());
     }
 
     #[rstest]
@@ -175,6 +163,6 @@ mod tests {
 48 48 47 44 41 40";
         let solution = Day02 {};
         let parsed = solution.parse(input).unwrap();
-        assert_eq!("1", solution.part2(parsed).unwrap().to_string());
+        assert_eq!("1", solution.part2(&parsed).unwrap().to_string());
     }
 }
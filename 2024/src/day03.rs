@@ -1,113 +1,288 @@
 use aoc_common::{ParseError, RunnerError, Solution};
-use models::Instruction;
+use models::{Do, Dont, Instruction, InstructionSet, Multiply};
 use regex::Regex;
-use std::fmt::Display;
+use std::fmt::{self, Display};
+
+/// Only `mul` instructions count for part 1; part 2 also has to keep track of
+/// whether they've been disabled.
+fn instruction_set(part: u8) -> InstructionSet {
+    match part {
+        1 => InstructionSet::new().with(Multiply),
+        _ => InstructionSet::new().with(Multiply).with(Do).with(Dont),
+    }
+}
 
 pub struct Day03 {}
 impl Solution for Day03 {
-    type Parsed = Vec<models::Operation>;
+    type Parsed = Vec<Instruction>;
 
     fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
-        parser::parse(input)
+        parser::tokenize(input, &instruction_set(2))
     }
 
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        Ok(input.iter().map(|operation| operation.product()).sum::<u64>())
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        Ok(instruction_set(1).run(input).accumulator)
     }
 
-    fn part2(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        Ok(input.iter().filter(|o| o.enabled()).map(|o| o.product()).sum::<u64>())
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        Ok(instruction_set(2).run(input).accumulator)
     }
 }
 
+/// The same solution, but finding instructions with a regular expression
+/// built from each handler's pattern instead of the tokenizer.
 pub struct Day03WithRegex {}
+impl Day03WithRegex {
+    /// Match any of the instructions, then work out which one it was (and
+    /// capture its arguments) with that handler's pattern alone. Earlier
+    /// handlers win when several match at the same position, like they do
+    /// for the tokenizer.
+    fn tokenize(memory: &str, instructions: &InstructionSet) -> Result<Vec<Instruction>, ParseError> {
+        let compile = |pattern: &str| Regex::new(pattern).map_err(|err| ParseError::Custom(err.to_string()));
+        let handlers = instructions
+            .handlers()
+            .iter()
+            .map(|handler| Ok((handler.name(), compile(&format!("^(?:{})$", handler.pattern()))?)))
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        let patterns: Vec<String> =
+            instructions.handlers().iter().map(|handler| format!("(?:{})", handler.pattern())).collect();
+        let any = compile(&patterns.join("|"))?;
+        let mut tokens = Vec::new();
+        for found in any.find_iter(memory) {
+            let Some((name, captures)) =
+                handlers.iter().find_map(|(name, pattern)| Some((*name, pattern.captures(found.as_str())?)))
+            else {
+                continue;
+            };
+            let arguments = captures
+                .iter()
+                .skip(1)
+                .flatten()
+                .map(|argument| argument.as_str().parse().map_err(ParseError::Int))
+                .collect::<Result<Vec<u32>, ParseError>>()?;
+            tokens.push(Instruction::new(name, arguments, found.start()));
+        }
+        Ok(tokens)
+    }
+}
 impl Solution for Day03WithRegex {
-    type Parsed = Vec<models::Instruction>;
+    type Parsed = Vec<Instruction>;
 
     fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
-        let re = Regex::new(r"(?:do\(\)|don't\(\)|mul\((?<first>\d{0,3}),(?<second>\d{0,3})\))").unwrap();
-        Ok(re
-            .captures_iter(input)
-            .map(|captures| match &captures.get(0).unwrap().as_str()[..3] {
-                "do(" => Instruction::Do,
-                "don" => Instruction::Dont,
-                "mul" => Instruction::Multiply(
-                    captures.name("first").unwrap().as_str().parse().unwrap(),
-                    captures.name("second").unwrap().as_str().parse().unwrap(),
-                ),
-                _ => unreachable!(),
-            })
-            .collect::<Vec<_>>())
-    }
-
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        Ok(input
-            .iter()
-            .filter_map(|instruction| match instruction {
-                Instruction::Multiply(first, second) => Some((first * second) as u64),
-                _ => None,
-            })
-            .sum::<u64>())
-    }
-
-    fn part2(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        let mut enabled = true;
-        let mut result = 0;
-        for instruction in input.into_iter() {
-            match instruction {
-                Instruction::Do => enabled = true,
-                Instruction::Dont => enabled = false,
-                Instruction::Multiply(first, second) => {
-                    if enabled {
-                        result += (first * second) as u64;
-                    }
-                },
-            }
-        }
-        Ok(result)
+        Self::tokenize(input, &instruction_set(2))
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        Ok(instruction_set(1).run(input).accumulator)
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        Ok(instruction_set(2).run(input).accumulator)
+    }
+}
+
+/// The first instruction that the two front-ends found differently.
+#[derive(Debug, PartialEq)]
+pub struct Divergence {
+    /// Which instruction (counting from zero) they disagreed on.
+    pub index: usize,
+    pub nom: Option<Instruction>,
+    pub regex: Option<Instruction>,
+}
+impl Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let describe = |found: &Option<Instruction>| match found {
+            Some(instruction) => format!("{instruction} at byte {}", instruction.offset),
+            None => "nothing".to_string(),
+        };
+        write!(
+            f,
+            "Instruction #{} differs: nom found {}, but regex found {}",
+            self.index + 1,
+            describe(&self.nom),
+            describe(&self.regex)
+        )
     }
 }
 
+/// Run both front-ends over the same memory, to check that a change to either
+/// of them (or to a handler) hasn't made them disagree about what the memory
+/// contains.
+pub fn differential(memory: &str) -> Result<Option<Divergence>, ParseError> {
+    let tokens = Day03 {}.parse(memory)?;
+    let regex = Day03WithRegex {}.parse(memory)?;
+    let diverged = (0..tokens.len().max(regex.len())).find(|&i| tokens.get(i) != regex.get(i));
+    Ok(diverged.map(|index| Divergence {
+        index,
+        nom: tokens.get(index).cloned(),
+        regex: regex.get(index).cloned(),
+    }))
+}
+
 mod models {
+    use super::parser;
+    use nom::{bytes::complete::tag, combinator::value, IResult};
+    use std::fmt::{self, Display};
+
+    /// An instruction found in the corrupted memory.
     #[derive(Clone, Debug, PartialEq)]
-    pub struct Operation {
-        input: (u32, u32),
-        enabled: bool,
+    pub struct Instruction {
+        pub(crate) name: &'static str,
+        pub(crate) arguments: Vec<u32>,
+        /// How far into the memory the instruction starts, in bytes.
+        pub(crate) offset: usize,
+    }
+    impl Instruction {
+        pub(crate) fn new(name: &'static str, arguments: Vec<u32>, offset: usize) -> Self {
+            Self { name, arguments, offset }
+        }
     }
-    impl Operation {
-        pub(crate) fn new(input: (u32, u32), enabled: bool) -> Self {
-            Self { input, enabled }
+    impl Display for Instruction {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            let arguments: Vec<String> = self.arguments.iter().map(u32::to_string).collect();
+            write!(f, "{}({})", self.name, arguments.join(","))
+        }
+    }
+
+    /// The state that instructions act upon.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct Machine {
+        pub(crate) enabled: bool,
+        pub(crate) accumulator: u64,
+    }
+    impl Machine {
+        pub(crate) fn new() -> Self {
+            Self { enabled: true, accumulator: 0 }
+        }
+    }
+
+    /// A kind of instruction: how to recognise it in memory (including its
+    /// name and arguments), and what it does to the machine.
+    pub trait Handler: Send + Sync {
+        fn name(&self) -> &'static str;
+        fn parse<'a>(&self, input: &'a str) -> IResult<&'a str, Vec<u32>>;
+        /// A regular expression for (roughly) the same instructions as
+        /// `parse`, capturing each argument in its own group.
+        fn pattern(&self) -> &'static str;
+        fn execute(&self, machine: &mut Machine, arguments: &[u32]);
+    }
+
+    /// The instructions that get recognised, and run by the machine. Any other
+    /// instructions are skipped over.
+    #[derive(Default)]
+    pub struct InstructionSet {
+        handlers: Vec<Box<dyn Handler>>,
+    }
+    impl InstructionSet {
+        pub(crate) fn new() -> Self {
+            Self::default()
         }
 
-        pub(crate) fn product(&self) -> u64 {
-            (self.input.0 * self.input.1) as u64
+        pub(crate) fn with(mut self, handler: impl Handler + 'static) -> Self {
+            self.handlers.push(Box::new(handler));
+            self
         }
 
-        pub(crate) fn enabled(&self) -> bool {
-            self.enabled
+        pub(crate) fn handlers(&self) -> &[Box<dyn Handler>] {
+            &self.handlers
+        }
+
+        pub(crate) fn run(&self, instructions: &[Instruction]) -> Machine {
+            let mut machine = Machine::new();
+            for instruction in instructions {
+                if let Some(handler) = self.handlers.iter().find(|handler| handler.name() == instruction.name) {
+                    handler.execute(&mut machine, &instruction.arguments);
+                }
+            }
+            machine
         }
     }
 
-    #[derive(Clone, Debug, PartialEq)]
-    pub enum Instruction {
-        Do,
-        Dont,
-        Multiply(u32, u32),
+    /// `mul(X,Y)` adds the product of its two arguments, while enabled.
+    pub struct Multiply;
+    impl Multiply {
+        pub(crate) const NAME: &'static str = "mul";
+    }
+    impl Handler for Multiply {
+        fn name(&self) -> &'static str {
+            Self::NAME
+        }
+
+        fn parse<'a>(&self, input: &'a str) -> IResult<&'a str, Vec<u32>> {
+            let (remaining, (first, second)) = parser::parse_mul(input)?;
+            Ok((remaining, vec![first, second]))
+        }
+
+        fn pattern(&self) -> &'static str {
+            r"mul\((\d{1,3}),(\d{1,3})\)"
+        }
+
+        fn execute(&self, machine: &mut Machine, arguments: &[u32]) {
+            if machine.enabled {
+                machine.accumulator += arguments.iter().map(|&argument| argument as u64).product::<u64>();
+            }
+        }
+    }
+
+    /// `do()` enables multiplying.
+    pub struct Do;
+    impl Do {
+        pub(crate) const NAME: &'static str = "do";
+    }
+    impl Handler for Do {
+        fn name(&self) -> &'static str {
+            Self::NAME
+        }
+
+        fn parse<'a>(&self, input: &'a str) -> IResult<&'a str, Vec<u32>> {
+            value(vec![], tag("do()"))(input)
+        }
+
+        fn pattern(&self) -> &'static str {
+            r"do\(\)"
+        }
+
+        fn execute(&self, machine: &mut Machine, _arguments: &[u32]) {
+            machine.enabled = true;
+        }
+    }
+
+    /// `don't()` disables multiplying.
+    pub struct Dont;
+    impl Dont {
+        pub(crate) const NAME: &'static str = "don't";
+    }
+    impl Handler for Dont {
+        fn name(&self) -> &'static str {
+            Self::NAME
+        }
+
+        fn parse<'a>(&self, input: &'a str) -> IResult<&'a str, Vec<u32>> {
+            value(vec![], tag("don't()"))(input)
+        }
+
+        fn pattern(&self) -> &'static str {
+            r"don't\(\)"
+        }
+
+        fn execute(&self, machine: &mut Machine, _arguments: &[u32]) {
+            machine.enabled = false;
+        }
     }
 }
 
 mod parser {
-    use super::models::Operation;
+    use super::models::{Instruction, InstructionSet};
+    use crate::nom::{located, scan_for, Located};
     use aoc_common::ParseError;
     use nom::{
-        branch::alt,
         bytes::complete::tag,
-        character::complete::{anychar, satisfy},
+        character::complete::satisfy,
         combinator::map_res,
-        error::Error as NomError,
-        multi::{fold_many_m_n, many_till},
+        error::{Error as NomError, ErrorKind},
+        multi::{fold_many_m_n, many0},
         sequence::{delimited, separated_pair, tuple},
-        IResult,
+        Err as NomErr, IResult,
     };
 
     fn parse_number(input: &str) -> IResult<&str, u32> {
@@ -127,44 +302,38 @@ mod parser {
         separated_pair(parse_number, tag(","), parse_number)(input)
     }
 
-    fn parse_mul(input: &str) -> IResult<&str, (u32, u32)> {
+    pub(crate) fn parse_mul(input: &str) -> IResult<&str, (u32, u32)> {
         delimited(tag("mul("), parse_number_pair, tag(")"))(input)
     }
 
-    pub(crate) fn parse(mut input: &str) -> Result<Vec<Operation>, ParseError> {
-        let mut operations = Vec::new();
-        let mut enabled = true;
-        // Consider this a long-winded way of achieving the `take_until_parser`
-        // because the normal `take_until` only consumes a static tag.
-        'take: while let Ok((remaining, (_taken, instruction))) =
-            many_till(anychar::<_, NomError<_>>, alt((tag("mul("), tag("do()"), tag("don't()"))))(input)
-        {
-            // We want the remainder to include the instruction, so we can match again.
-            let consumed = input.len() - (instruction.len() + remaining.len());
-            let remaining = &input[consumed..];
-
-            match instruction {
-                "do()" => enabled = true,
-                "don't()" => enabled = false,
-                "mul(" => {
-                    if let Ok((remaining, operation)) = parse_mul(remaining) {
-                        operations.push(Operation::new(operation, enabled));
-                        input = remaining;
-                        continue 'take;
-                    }
-                },
-                _ => (),
-            };
-            // False positive, advance past what we just matched to
-            // prevent matching the same one over and over again.
-            input = &remaining[1..];
-        }
-        Ok(operations)
+    /// Find every instruction in the corrupted memory that one of the handlers
+    /// recognises. Anything that doesn't parse is skipped one character at a
+    /// time, so that an instruction can start inside a false positive.
+    pub(crate) fn tokenize(memory: &str, instructions: &InstructionSet) -> Result<Vec<Instruction>, ParseError> {
+        let instruction = |input| {
+            let mut handlers = instructions.handlers().iter();
+            handlers
+                .find_map(|handler| {
+                    handler.parse(input).ok().map(|(remaining, arguments)| (remaining, (handler.name(), arguments)))
+                })
+                .ok_or_else(|| NomErr::Error(NomError::new(input, ErrorKind::Alt)))
+        };
+        // Scanning stops at the end of the memory, once there are no more
+        // instructions, unless a handler matched without consuming anything.
+        let tokens = match many0(scan_for(located(memory, instruction)))(memory) {
+            Ok((_, tokens)) => tokens,
+            Err(err) => return Err(ParseError::nom(memory, err)),
+        };
+        Ok(tokens
+            .into_iter()
+            .map(|Located { value: (name, arguments), offset, .. }| Instruction::new(name, arguments, offset))
+            .collect())
     }
 
     #[cfg(test)]
     mod tests {
         use super::*;
+        use crate::day03::models::{Do, Dont, Multiply};
         use nom::error::{Error, ErrorKind};
         use rstest::rstest;
 
@@ -232,16 +401,18 @@ mod parser {
 
         #[rstest]
         #[case("", vec![])]
-        #[case("mul(1,2)", vec![(1, 2, true)])]
-        #[case("mul(1,2)mul(3,4)", vec![(1, 2, true), (3, 4, true)])]
-        #[case("mul(1,2) mul(3,4)", vec![(1, 2, true), (3, 4, true)])]
-        #[case("xmul(1,2)mul(mul(3,4)", vec![(1, 2, true), (3, 4, true)])]
-        #[case("do_not_mul(5,5)", vec![(5, 5, true)])]
+        #[case("mul(1,2)", vec!["mul(1,2)"])]
+        #[case("mul(1,2)mul(3,4)", vec!["mul(1,2)", "mul(3,4)"])]
+        #[case("mul(1,2) mul(3,4)", vec!["mul(1,2)", "mul(3,4)"])]
+        #[case("xmul(1,2)mul(mul(3,4)", vec!["mul(1,2)", "mul(3,4)"])]
+        #[case("do_not_mul(5,5)", vec!["mul(5,5)"])]
         #[case("mul[3,7]", vec![])]
-        fn test_parse_string(#[case] input: &str, #[case] expected: Vec<(u32, u32, bool)>) {
-            let expected =
-                expected.into_iter().map(|(first, second, enabled)| Operation::new((first, second), enabled)).collect();
-            assert_eq!(Ok(expected), parse(input));
+        #[case("don't()mul(1,2)do()", vec!["don't()", "mul(1,2)", "do()"])]
+        #[case("ümul(1,2)", vec!["mul(1,2)"])]
+        fn test_tokenize(#[case] input: &str, #[case] expected: Vec<&str>) {
+            let instructions = InstructionSet::new().with(Multiply).with(Do).with(Dont);
+            let tokens = tokenize(input, &instructions).unwrap();
+            assert_eq!(expected, tokens.iter().map(Instruction::to_string).collect::<Vec<_>>());
         }
     }
 }
@@ -249,78 +420,60 @@ mod parser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use models::Operation;
+    use aoc_common::example::Example;
+    use models::Machine;
+    use rstest::rstest;
+    use std::path::PathBuf;
 
-    const PART1_INPUT: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
-    const PART2_INPUT: &str = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+    const INPUT: &str = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
 
     #[test]
     fn test_parse() {
         let solution = Day03 {};
         let expected = vec![
-            Operation::new((2, 4), true),
-            Operation::new((5, 5), true),
-            Operation::new((11, 8), true),
-            Operation::new((8, 5), true),
+            Instruction::new(Multiply::NAME, vec![2, 4], 1),
+            Instruction::new(Multiply::NAME, vec![5, 5], 29),
+            Instruction::new(Multiply::NAME, vec![11, 8], 53),
+            Instruction::new(Multiply::NAME, vec![8, 5], 62),
         ];
-        let parsed = solution.parse(PART1_INPUT).unwrap();
+        let parsed = solution.parse(INPUT).unwrap();
         assert_eq!(expected, parsed);
     }
 
-    #[test]
-    fn test_part_one() {
-        let solution = Day03 {};
-        let parsed = solution.parse(PART1_INPUT).unwrap();
-        assert_eq!("161", solution.part1(parsed).unwrap().to_string());
-    }
+    aoc_common::example_tests!(Day03 {}, "../examples/2024/03/*.txt");
 
     #[test]
-    // Make sure we aren't getting any overflows.
-    fn test_part_one_overflow() {
-        let solution = Day03 {};
-        let parsed = solution.parse("mul(999,999)").unwrap();
-        assert_eq!("998001", solution.part1(parsed).unwrap().to_string());
+    fn test_parse_regex() {
+        let solution = Day03WithRegex {};
+        let parsed = solution.parse(INPUT).unwrap();
+        assert_eq!(Day03 {}.parse(INPUT).unwrap(), parsed);
     }
 
     #[test]
-    fn test_part_two() {
-        let solution = Day03 {};
-        let parsed = solution.parse(PART2_INPUT).unwrap();
-        assert_eq!("48", solution.part2(parsed).unwrap().to_string());
+    fn test_machine() {
+        let instructions = Day03 {}.parse("mul(2,3)don't()mul(4,5)do()mul(6,7)").unwrap();
+        assert_eq!(Machine { enabled: true, accumulator: 68 }, instruction_set(1).run(&instructions));
+        assert_eq!(Machine { enabled: true, accumulator: 48 }, instruction_set(2).run(&instructions));
+        let disabled = &instructions[..2];
+        assert_eq!(Machine { enabled: false, accumulator: 6 }, instruction_set(2).run(disabled));
     }
 
-    #[test]
-    fn test_parse_regex() {
-        let solution = Day03WithRegex {};
-        let expected = vec![
-            Instruction::Multiply(2, 4),
-            Instruction::Multiply(5, 5),
-            Instruction::Multiply(11, 8),
-            Instruction::Multiply(8, 5),
-        ];
-        let parsed = solution.parse(PART1_INPUT).unwrap();
-        assert_eq!(expected, parsed);
+    #[rstest]
+    fn test_front_ends_agree(#[files("../examples/2024/03/*.txt")] path: PathBuf) {
+        let example = Example::load(&path).unwrap();
+        assert_eq!(None, differential(&example.input).unwrap(), "{}", path.display());
     }
 
     #[test]
-    fn test_part_one_regex() {
-        let solution = Day03WithRegex {};
-        let parsed = solution.parse(PART1_INPUT).unwrap();
-        assert_eq!("161", solution.part1(parsed).unwrap().to_string());
+    fn test_front_ends_diverge() {
+        let diverged = differential("mul(1,2)do()mul(012,3)don't()").unwrap().unwrap();
+        let expected = "Instruction #3 differs: nom found don't() at byte 22, but regex found mul(12,3) at byte 12";
+        assert_eq!(expected, diverged.to_string());
     }
 
-    #[test]
-    // Make sure we aren't getting any overflows.
-    fn test_part_one_overflow_regex() {
-        let solution = Day03WithRegex {};
-        let parsed = solution.parse("mul(999,999)").unwrap();
-        assert_eq!("998001", solution.part1(parsed).unwrap().to_string());
-    }
+    mod regex {
+        use super::*;
 
-    #[test]
-    fn test_part_two_regex() {
-        let solution = Day03WithRegex {};
-        let parsed = solution.parse(PART2_INPUT).unwrap();
-        assert_eq!("48", solution.part2(parsed).unwrap().to_string());
+        aoc_common::example_tests!(Day03WithRegex {}, "../examples/2024/03/*.txt");
     }
 }
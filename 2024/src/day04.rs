@@ -1,159 +1,61 @@
-use aoc_common::{ParseError, RunnerError, Solution};
-use models::{Direction, Words, XmasDiscovery};
+use aoc_common::visualize::{Frames, Visualize};
+use aoc_common::{AocError, ParseError, RunnerError, Solution};
+use models::{Direction, Stencil};
 use std::{collections::BTreeMap, fmt::Display};
 
+/// Two MASes crossing over each other, in the shape of an X.
+const X_MAS: &str = "M.S\n.A.\nM.S";
+
 pub struct Day04 {}
 impl Solution for Day04 {
     type Parsed = models::WordSearch;
 
     fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
-        let lines = input.trim().lines();
-        let mut height = 0;
-        let mut width: Option<usize> = None;
+        let rows = aoc_common::input::grid(input)?;
         let mut characters = BTreeMap::new();
-        lines.enumerate().for_each(|(y, line)| {
-            height = y;
-            let mut line_width = 0;
-
-            line.trim().chars().enumerate().for_each(|(x, c)| {
-                line_width = x;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
                 characters.insert(models::Position::new(x + 1, y + 1), c);
-            });
-            line_width += 1;
-            if &line_width != width.get_or_insert(line_width) {
-                // TODO: Bubble up this error instead of a panic.
-                panic!("input contains variable width word search");
             }
-        });
-        Ok(models::WordSearch::new(
-            characters,
-            width.ok_or_else(|| ParseError::Custom("Undeterminable word search width".to_string()))?,
-            height + 1,
-        ))
+        }
+        Ok(models::WordSearch::new(characters, rows[0].chars().count(), rows.len()))
     }
 
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        let x_char_positions =
-            input.characters().iter().filter(|(_, c)| c == &&'X').map(|(position, _)| position).collect::<Vec<_>>();
-        let mut words: Vec<XmasDiscovery> = Vec::new();
-        for position in x_char_positions {
-            let south_enough = position.y >= 4;
-            let east_enough = position.x >= 4;
-            let north_enough = position.y <= (input.height() - 4);
-            let west_enough = position.x <= (input.width() - 4);
-
-            if south_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x, position.y - 1).unwrap(),
-                    input.get_char(position.x, position.y - 2).unwrap(),
-                    input.get_char(position.x, position.y - 3).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::North, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if south_enough && west_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x + 1, position.y - 1).unwrap(),
-                    input.get_char(position.x + 2, position.y - 2).unwrap(),
-                    input.get_char(position.x + 3, position.y - 3).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::NorthEast, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if west_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x + 1, position.y).unwrap(),
-                    input.get_char(position.x + 2, position.y).unwrap(),
-                    input.get_char(position.x + 3, position.y).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::East, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if west_enough && north_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x + 1, position.y + 1).unwrap(),
-                    input.get_char(position.x + 2, position.y + 2).unwrap(),
-                    input.get_char(position.x + 3, position.y + 3).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::SouthEast, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if north_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x, position.y + 1).unwrap(),
-                    input.get_char(position.x, position.y + 2).unwrap(),
-                    input.get_char(position.x, position.y + 3).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::South, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if north_enough && east_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x - 1, position.y + 1).unwrap(),
-                    input.get_char(position.x - 2, position.y + 2).unwrap(),
-                    input.get_char(position.x - 3, position.y + 3).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::SouthWest, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if east_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x - 1, position.y).unwrap(),
-                    input.get_char(position.x - 2, position.y).unwrap(),
-                    input.get_char(position.x - 3, position.y).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::West, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-            if east_enough && south_enough {
-                let word = (
-                    input.get_char(position.x, position.y).unwrap(),
-                    input.get_char(position.x - 1, position.y - 1).unwrap(),
-                    input.get_char(position.x - 2, position.y - 2).unwrap(),
-                    input.get_char(position.x - 3, position.y - 3).unwrap(),
-                );
-                let discovery = XmasDiscovery(position.clone(), Direction::NorthWest, word);
-                if discovery.valid() {
-                    words.push(discovery);
-                }
-            }
-        }
-        let output = Words { words };
-        eprintln!("{output:?}");
-        Ok(output)
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        Ok(input.find_word("XMAS", &Direction::ALL).len())
     }
 
-    fn part2(&self, _input: Self::Parsed) -> Result<impl Display, RunnerError> {
-        Err::<usize, RunnerError>(RunnerError::Unimplemented)
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        let x_mas = Stencil::parse(X_MAS);
+        Ok(x_mas.rotations().iter().map(|stencil| input.find_stencil(stencil).len()).sum::<usize>())
+    }
+}
+
+/// Highlights every XMAS one at a time as they're found, followed by every X-MAS.
+impl Visualize for Day04 {
+    fn frames(&self, input: &str) -> Result<Frames, AocError> {
+        let search = self.parse(input)?;
+        let words: Vec<Vec<_>> =
+            search.find_word("XMAS", &Direction::ALL).iter().map(|found| found.positions().collect()).collect();
+        let crosses: Vec<Vec<_>> = Stencil::parse(X_MAS)
+            .rotations()
+            .iter()
+            .flat_map(|stencil| search.find_stencil(stencil))
+            .map(|found| found.positions)
+            .collect();
+        let found = [("XMAS", words), ("X-MAS", crosses)];
+        Ok(Box::new(found.into_iter().flat_map(move |(name, matches)| {
+            let search = search.clone();
+            (0..=matches.len()).map(move |count| {
+                let highlighted = matches[..count].iter().flatten().copied();
+                format!("{name} found: {count}\n{}", search.render(highlighted))
+            })
+        })))
     }
 }
 
 mod models {
-    use std::{
-        collections::BTreeMap,
-        fmt::{Debug, Display},
-    };
+    use std::collections::{BTreeMap, BTreeSet};
 
     #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
     pub(crate) struct Position {
@@ -164,9 +66,14 @@ mod models {
         pub(crate) fn new(x: usize, y: usize) -> Self {
             Self { x, y }
         }
+
+        /// Move by an offset, unless that would leave the (unsigned) coordinate space.
+        pub(crate) fn offset(&self, dx: isize, dy: isize) -> Option<Self> {
+            Some(Self::new(self.x.checked_add_signed(dx)?, self.y.checked_add_signed(dy)?))
+        }
     }
 
-    #[derive(Clone, Debug, PartialEq)]
+    #[derive(Clone, Copy, Debug, PartialEq)]
     pub(crate) enum Direction {
         North,
         NorthEast,
@@ -177,6 +84,97 @@ mod models {
         West,
         NorthWest,
     }
+    impl Direction {
+        pub(crate) const ALL: [Self; 8] = [
+            Self::North,
+            Self::NorthEast,
+            Self::East,
+            Self::SouthEast,
+            Self::South,
+            Self::SouthWest,
+            Self::West,
+            Self::NorthWest,
+        ];
+
+        /// How far one step in this direction moves, with North being up the grid.
+        pub(crate) fn delta(&self) -> (isize, isize) {
+            match self {
+                Self::North => (0, -1),
+                Self::NorthEast => (1, -1),
+                Self::East => (1, 0),
+                Self::SouthEast => (1, 1),
+                Self::South => (0, 1),
+                Self::SouthWest => (-1, 1),
+                Self::West => (-1, 0),
+                Self::NorthWest => (-1, -1),
+            }
+        }
+    }
+
+    /// A word found in the word search, read from its first letter onwards.
+    #[derive(Clone, Debug, PartialEq)]
+    pub(crate) struct WordMatch {
+        pub(crate) start: Position,
+        pub(crate) direction: Direction,
+        pub(crate) length: usize,
+    }
+    impl WordMatch {
+        /// The position of each letter of the word.
+        pub(crate) fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+            let (dx, dy) = self.direction.delta();
+            (0..self.length as isize).filter_map(move |step| self.start.offset(dx * step, dy * step))
+        }
+    }
+
+    /// A two-dimensional pattern of characters, where `.` matches anything.
+    #[derive(Clone, Debug, PartialEq)]
+    pub(crate) struct Stencil {
+        /// The offset of every character that has to match, from the top-left corner of the pattern.
+        cells: Vec<(usize, usize, char)>,
+        width: usize,
+        height: usize,
+    }
+    impl Stencil {
+        pub(crate) const WILDCARD: char = '.';
+
+        pub(crate) fn parse(pattern: &str) -> Self {
+            let rows: Vec<&str> = pattern.lines().collect();
+            let cells = rows
+                .iter()
+                .enumerate()
+                .flat_map(|(y, row)| row.chars().enumerate().map(move |(x, c)| (x, y, c)))
+                .filter(|(_, _, c)| *c != Self::WILDCARD)
+                .collect();
+            let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or_default();
+            Self { cells, width, height: rows.len() }
+        }
+
+        /// The stencil turned a quarter clockwise.
+        fn rotate(&self) -> Self {
+            let mut cells: Vec<_> = self.cells.iter().map(|&(x, y, c)| (self.height - 1 - y, x, c)).collect();
+            cells.sort_by_key(|&(x, y, _)| (y, x));
+            Self { cells, width: self.height, height: self.width }
+        }
+
+        /// Every distinct way the stencil can be turned, starting with how it is now.
+        pub(crate) fn rotations(&self) -> Vec<Self> {
+            let mut rotations = vec![self.clone()];
+            for _ in 1..4 {
+                let next = rotations[rotations.len() - 1].rotate();
+                if !rotations.contains(&next) {
+                    rotations.push(next);
+                }
+            }
+            rotations
+        }
+    }
+
+    /// Where a [`Stencil`] matched, and the positions of the characters that it matched.
+    #[derive(Clone, Debug, PartialEq)]
+    pub(crate) struct StencilMatch {
+        pub(crate) origin: Position,
+        pub(crate) positions: Vec<Position>,
+    }
 
     #[derive(Clone, Debug, PartialEq)]
     pub struct WordSearch {
@@ -189,10 +187,6 @@ mod models {
             Self { characters, width, height }
         }
 
-        pub(crate) fn characters(&self) -> &BTreeMap<Position, char> {
-            &self.characters
-        }
-
         pub(crate) fn width(&self) -> usize {
             self.width
         }
@@ -201,25 +195,67 @@ mod models {
             self.height
         }
 
-        pub(crate) fn get_char(&self, x: usize, y: usize) -> Option<char> {
-            self.characters.get(&Position { x, y }).copied()
+        pub(crate) fn get(&self, position: Position) -> Option<char> {
+            self.characters.get(&position).copied()
         }
-    }
 
-    #[derive(Clone, Debug, PartialEq)]
-    pub(crate) struct XmasDiscovery(pub(crate) Position, pub(crate) Direction, pub(crate) (char, char, char, char));
-    impl XmasDiscovery {
-        pub(crate) fn valid(&self) -> bool {
-            return self.2 == ('X', 'M', 'A', 'S');
+        /// Every occurrence of a word, read in any of the given directions.
+        pub(crate) fn find_word(&self, word: &str, directions: &[Direction]) -> Vec<WordMatch> {
+            let letters: Vec<char> = word.chars().collect();
+            let Some(first) = letters.first() else {
+                return Vec::new();
+            };
+            let length = letters.len();
+            let starts = self.characters.iter().filter(|(_, c)| *c == first).map(|(position, _)| *position);
+            starts
+                .flat_map(|start| directions.iter().map(move |&direction| WordMatch { start, direction, length }))
+                .filter(|candidate| {
+                    let found = candidate.positions().map(|position| self.get(position));
+                    found.eq(letters.iter().map(|&letter| Some(letter)))
+                })
+                .collect()
         }
-    }
-    #[derive(Clone, Debug, PartialEq)]
-    pub(crate) struct Words {
-        pub(crate) words: Vec<XmasDiscovery>,
-    }
-    impl Display for Words {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-            write!(f, "{}", self.words.len())
+
+        /// Every place that a stencil matches, without turning it.
+        pub(crate) fn find_stencil(&self, stencil: &Stencil) -> Vec<StencilMatch> {
+            if stencil.cells.is_empty() {
+                return Vec::new();
+            }
+            // Positions start from 1, so the stencil fits as long as its far corner does.
+            let fits = |origin: &Position| {
+                origin.x + stencil.width <= self.width() + 1 && origin.y + stencil.height <= self.height() + 1
+            };
+            self.characters
+                .keys()
+                .filter(|origin| fits(origin))
+                .filter_map(|&origin| {
+                    let positions = stencil
+                        .cells
+                        .iter()
+                        .map(|&(dx, dy, c)| {
+                            Some(Position::new(origin.x + dx, origin.y + dy))
+                                .filter(|&position| self.get(position) == Some(c))
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    Some(StencilMatch { origin, positions })
+                })
+                .collect()
+        }
+
+        /// The word search with every character that isn't at one of the given
+        /// positions replaced by a `.`, to show where matches were found.
+        pub(crate) fn render(&self, highlighted: impl IntoIterator<Item = Position>) -> String {
+            let highlighted: BTreeSet<Position> = highlighted.into_iter().collect();
+            let rows = (1..=self.height).map(|y| {
+                (1..=self.width)
+                    .map(|x| Position::new(x, y))
+                    .map(|position| match highlighted.contains(&position) {
+                        true => self.get(position).unwrap_or(' '),
+                        false => '.',
+                    })
+                    .collect::<String>()
+            });
+            rows.collect::<Vec<_>>().join("\n")
         }
     }
 }
@@ -252,28 +288,61 @@ S.S.S.S.SS
 ..M.M.M.MM
 .X.X.XMASX";
 
-    const PART2_SOLVED: &str = "";
+    const PART2_SOLVED: &str = ".M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+..........";
 
     #[test]
     fn test_parse() {
         let solution = Day04 {};
         let parsed = solution.parse(INPUT).unwrap();
-        // assert_eq!(Words { words: vec![] }, parsed);
+        assert_eq!((10, 10), (parsed.width(), parsed.height()));
     }
 
+    aoc_common::example_tests!(Day04 {}, "../examples/2024/04/*.txt");
+
     #[test]
     fn test_part_one() {
         let solution = Day04 {};
         let parsed = solution.parse(INPUT).unwrap();
-        eprintln!("{parsed:?}");
-        let output = solution.part1(parsed).unwrap();
-        assert_eq!("19", output.to_string());
+        assert_eq!("18", solution.part1(&parsed).unwrap().to_string());
     }
 
     #[test]
     fn test_part_two() {
         let solution = Day04 {};
         let parsed = solution.parse(INPUT).unwrap();
-        assert_eq!("", solution.part2(parsed).unwrap().to_string());
+        assert_eq!("9", solution.part2(&parsed).unwrap().to_string());
+    }
+
+    #[test]
+    fn test_find_word() {
+        let parsed = Day04 {}.parse(INPUT).unwrap();
+        let matches = parsed.find_word("XMAS", &Direction::ALL);
+        assert_eq!(PART1_SOLVED, parsed.render(matches.iter().flat_map(models::WordMatch::positions)));
+        let backwards = parsed.find_word("XMAS", &[Direction::West]);
+        assert_eq!(
+            vec![models::Position::new(5, 2), models::Position::new(7, 5)],
+            backwards.iter().map(|found| found.start).collect::<Vec<_>>()
+        );
+        assert!(parsed.find_word("", &Direction::ALL).is_empty());
+    }
+
+    #[test]
+    fn test_find_stencil() {
+        let parsed = Day04 {}.parse(INPUT).unwrap();
+        let x_mas = Stencil::parse(X_MAS);
+        assert_eq!(4, x_mas.rotations().len());
+        let matches: Vec<_> = x_mas.rotations().iter().flat_map(|stencil| parsed.find_stencil(stencil)).collect();
+        assert_eq!(PART2_SOLVED, parsed.render(matches.into_iter().flat_map(|found| found.positions)));
+        // Symmetrical stencils only get matched once.
+        assert_eq!(1, Stencil::parse("A.A\n.A.\nA.A").rotations().len());
     }
 }
@@ -1,4 +1,24 @@
+use aoc_common::{register_days, registry::Registry};
+
+pub mod day00;
 pub mod day01;
 pub mod day02;
 pub mod day03;
 pub mod day04;
+pub mod nom;
+
+/// Every solution for the year; register a new day here after adding its module.
+pub fn solutions() -> Registry {
+    let mut registry = register_days! {
+        1 => day01::Day01 {},
+        2 => day02::Day02 {},
+        3 => day03::Day03 {},
+        4 => day04::Day04 {},
+    };
+    // Day00 is a practice run on an older puzzle: it can be watched or graphed,
+    // but isn't a day of this year, so `all` shouldn't try to run or fetch it.
+    registry.register_visualization(0, day00::Day00 {});
+    registry.register_diagram(0, day00::Day00 {});
+    registry.register_visualization(4, day04::Day04 {});
+    registry
+}
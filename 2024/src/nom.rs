@@ -1,12 +1,38 @@
 use ::nom::{
-    error::{ErrorKind as NomErrorKind, ParseError as NomParseError},
-    multi::many_till,
-    Err as NomErr, IResult, InputIter, InputLength, InputTake, Parser as NomParser, Slice,
+    error::{ErrorKind as NomErrorKind, ParseError as NomParseError, VerboseError, VerboseErrorKind},
+    Err as NomErr, IResult, Parser as NomParser,
 };
-use std::ops::{RangeFrom, RangeTo};
+use aoc_common::ParseError;
+use std::fmt::Write;
+
+/// Errors that can be reported from an earlier point in the input, without
+/// losing what went wrong.
+pub trait Rewind<I> {
+    fn rewind(self, input: I) -> Self;
+}
+impl<I> Rewind<I> for ::nom::error::Error<I> {
+    fn rewind(self, input: I) -> Self {
+        Self::new(input, self.code)
+    }
+}
+impl<I> Rewind<I> for (I, NomErrorKind) {
+    fn rewind(self, input: I) -> Self {
+        (input, self.1)
+    }
+}
+impl<I> Rewind<I> for VerboseError<I> {
+    fn rewind(mut self, input: I) -> Self {
+        // Keep the context that the child failed in, but start from where it was applied.
+        if let Some((first, _)) = self.errors.first_mut() {
+            *first = input;
+        }
+        self
+    }
+}
 
 /// Only consumes input if the child parser successfully matches (note: the
-/// input must implement the Copy trait).
+/// input must implement the Copy trait). Otherwise, the child's error is
+/// reported from where it started, rather than from wherever it gave up.
 ///
 /// # Arguments
 /// * `parser` The child parser to apply.
@@ -15,40 +41,128 @@ use std::ops::{RangeFrom, RangeTo};
 /// use nom::{Err, error::ErrorKind};
 /// use nom::sequence::terminated;
 /// use nom::bytes::complete::tag;
-/// use aoc_2024::all_or_nothing
+/// use aoc_2024::nom::all_or_nothing;
 ///
 /// let mut child = terminated(tag("abc"), tag("efg"));
 /// let mut parser = all_or_nothing(terminated(tag("abc"), tag("efg")));
 ///
 /// assert_eq!(child("abcefj"), Err(Err::Error(("efj", ErrorKind::Tag))));
-/// assert_eq!(parser("abcefj"), Err(Err::Error(("abcefj", ErrorKind::Satisfy))));
+/// assert_eq!(parser("abcefj"), Err(Err::Error(("abcefj", ErrorKind::Tag))));
 /// ```
-pub(crate) fn all_or_nothing<I: Copy, O, E, F>(mut parser: F) -> impl FnMut(I) -> IResult<I, O, E>
+pub fn all_or_nothing<I: Copy, O, E, F>(mut parser: F) -> impl FnMut(I) -> IResult<I, O, E>
 where
-    E: NomParseError<I>,
+    E: Rewind<I>,
     F: NomParser<I, O, E>,
 {
-    move |input: I| {
-        let original = input;
+    move |input: I| match parser.parse(input) {
+        Ok((remaining, res)) => Ok((remaining, res)),
+        Err(NomErr::Incomplete(needed)) => Err(NomErr::Incomplete(needed)),
+        Err(NomErr::Error(err) | NomErr::Failure(err)) => Err(NomErr::Error(err.rewind(input))),
+    }
+}
+
+/// Skip over the input one character at a time until the child parser
+/// matches, like `many_till(anychar, parser)` but without collecting whatever
+/// got skipped. Fails at the end of the input if the child never matches.
+///
+/// ```rust
+/// use nom::bytes::complete::tag;
+/// use aoc_2024::nom::scan_for;
+///
+/// let mut parser = scan_for::<_, (&str, nom::error::ErrorKind), _>(tag("do()"));
+/// assert_eq!(Ok(("!", "do()")), parser("junkdo()!"));
+/// assert!(parser("nothing to see here").is_err());
+/// ```
+pub fn scan_for<'a, O, E, F>(mut parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+where
+    E: NomParseError<&'a str>,
+    F: NomParser<&'a str, O, E>,
+{
+    move |mut input: &'a str| loop {
         match parser.parse(input) {
-            Ok((remaining, res)) => Ok((remaining, res)),
-            Err(NomErr::Incomplete(needed)) => Err(NomErr::Incomplete(needed)),
-            // It would be really nice to re-use the error kind from the parse
-            // error, but we referenced a trait which doesn't expose the ErrorKind :(
-            Err(_) => Err(NomErr::Error(E::from_error_kind(original, NomErrorKind::Satisfy))),
+            Err(NomErr::Error(_)) => (),
+            result => return result,
+        }
+        let mut chars = input.chars();
+        if chars.next().is_none() {
+            return Err(NomErr::Error(E::from_error_kind(input, NomErrorKind::TakeUntil)));
         }
+        input = chars.as_str();
     }
 }
 
+/// The output of a parser, along with where it was found in the whole input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Located<O> {
+    pub value: O,
+    /// How far into the whole input the match starts, in bytes.
+    pub offset: usize,
+    /// How many bytes the match took up.
+    pub length: usize,
+}
+
+/// Record where the child parser matched, as a byte offset into `whole`: the
+/// input that parsing started from, which the child's input must be a suffix of.
+///
+/// ```rust
+/// use nom::bytes::complete::tag;
+/// use aoc_2024::nom::{located, scan_for, Located};
+///
+/// let input = "junkdo()!";
+/// let mut parser = scan_for::<_, (&str, nom::error::ErrorKind), _>(located(input, tag("do()")));
+/// assert_eq!(Ok(("!", Located { value: "do()", offset: 4, length: 4 })), parser(input));
+/// ```
+pub fn located<'a, O, E, F>(whole: &'a str, mut parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, Located<O>, E>
+where
+    F: NomParser<&'a str, O, E>,
+{
+    move |input: &'a str| {
+        let (remaining, value) = parser.parse(input)?;
+        let located = Located {
+            value,
+            offset: whole.len() - input.len(),
+            length: input.len() - remaining.len(),
+        };
+        Ok((remaining, located))
+    }
+}
+
+/// Convert the error returned by a Nom parser that uses [`VerboseError`],
+/// describing what was expected along with every context (added with
+/// `nom::error::context`) that it was expected in, innermost first.
+pub fn parse_error(input: &str, err: NomErr<VerboseError<&str>>) -> ParseError {
+    let err = match err {
+        NomErr::Incomplete(needed) => return ParseError::Nom(format!("Incomplete input: {needed:?}")),
+        NomErr::Error(err) | NomErr::Failure(err) => err,
+    };
+    let Some((remaining, kind)) = err.errors.first() else {
+        return ParseError::Nom("Parser failed without saying why".to_string());
+    };
+    let mut message = match kind {
+        VerboseErrorKind::Context(context) => format!("Expected {context}"),
+        VerboseErrorKind::Char(c) => format!("Expected '{c}'"),
+        VerboseErrorKind::Nom(NomErrorKind::Eof) => "Unexpected input".to_string(),
+        VerboseErrorKind::Nom(kind) => format!("Expected {}", kind.description()),
+    };
+    for (_, kind) in &err.errors[1..] {
+        if let VerboseErrorKind::Context(context) = kind {
+            write!(message, " in {context}").expect("Writing to a String cannot fail");
+        }
+    }
+    ParseError::at(input, remaining, message)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use nom::bytes::complete::tag;
+    use nom::character::complete::{char, digit1};
+    use nom::error::{context, ErrorKind};
+    use nom::sequence::{delimited, terminated};
 
     #[test]
     fn test_all_or_nothing() {
-        use nom::bytes::complete::tag;
-        use nom::sequence::terminated;
-        use nom::{error::ErrorKind, Err};
+        use nom::Err;
 
         let mut child = terminated(tag("abc"), tag("efg"));
         assert_eq!(child("abcefghij"), Ok(("hij", "abc")));
@@ -57,6 +171,36 @@ mod tests {
         let child = terminated(tag("abc"), tag("efg"));
         let mut parser = all_or_nothing(child);
         assert_eq!(parser("abcefghij"), Ok(("hij", "abc")));
-        assert_eq!(parser("abcefj"), Err(Err::Error(("abcefj", ErrorKind::Satisfy))));
+        assert_eq!(parser("abcefj"), Err(Err::Error(("abcefj", ErrorKind::Tag))));
+    }
+
+    #[test]
+    fn test_scan_for() {
+        let mut parser = scan_for::<_, (&str, ErrorKind), _>(delimited(tag("("), digit1, tag(")")));
+        assert_eq!(Ok(("", "12")), parser("(a)(12)"));
+        assert_eq!(Ok((" (3)", "12")), parser("ü(12) (3)"));
+        assert_eq!(Err(NomErr::Error(("", ErrorKind::TakeUntil))), parser("(a)"));
+    }
+
+    #[test]
+    fn test_located() {
+        let input = "ab(1)(23)";
+        let mut parser = located::<_, (&str, ErrorKind), _>(input, delimited(tag("("), digit1, tag(")")));
+        assert_eq!(Ok(("", Located { value: "23", offset: 5, length: 4 })), parser(&input[5..]));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        const INPUT: &str = "(1)\n(2";
+        fn number(input: &str) -> IResult<&str, &str, VerboseError<&str>> {
+            context("a number", delimited(char('('), digit1, char(')')))(input)
+        }
+        let mut numbers = terminated(context("a pair", terminated(number, tag("\n"))), number);
+        let err = parse_error(INPUT, numbers(INPUT).unwrap_err());
+        assert_eq!("Expected ')' in a number at line 2, column 3:\n2 | (2\n  |   ^", err.to_string());
+
+        let mut pair = context("a pair", all_or_nothing(tag::<_, _, VerboseError<&str>>("ab")));
+        let err = parse_error("ac", pair("ac").unwrap_err());
+        assert_eq!("Expected Tag in a pair at line 1, column 1:\n1 | ac\n  | ^", err.to_string());
     }
 }
@@ -71,9 +71,7 @@ impl<T> Grid<T> {
     where
         F: Fn(char) -> Result<T, E>,
     {
-        let elements: Result<BTreeMap<Position, T>, E> = input
-            .trim_start()
-            .lines()
+        let elements: Result<BTreeMap<Position, T>, E> = crate::input::lines(input)
             .enumerate()
             .flat_map(|(y, line)| {
                 line.chars()
@@ -111,6 +109,22 @@ impl<T> Grid<T> {
     pub fn remove(&mut self, position: &Position) -> Option<T> {
         self.elements.remove(position)
     }
+
+    /// Draw the grid as text, one line per row, with `symbol` deciding what
+    /// each position looks like (including any missing from the grid).
+    pub fn render(&self, symbol: impl Fn(&Position, Option<&T>) -> char) -> String {
+        let width = self.elements.keys().map(|position| position.x).max().unwrap_or_default();
+        let height = self.elements.keys().map(|position| position.y).max().unwrap_or_default();
+        let mut text = String::with_capacity((width + 1) * height);
+        for y in 1..=height {
+            for x in 1..=width {
+                let position = Position::new(x, y);
+                text.push(symbol(&position, self.get(&position)));
+            }
+            text.push('\n');
+        }
+        text
+    }
 }
 
 impl Grid<char> {
@@ -149,4 +163,11 @@ ghi
         let grid: Grid<char> = Grid::from_chars(TEST_INPUT);
         assert_eq!(grid, construct());
     }
+
+    #[test]
+    fn test_render() {
+        let mut grid = construct();
+        grid.remove(&Position::new(2, 2));
+        assert_eq!("abc\nd.f\nghi\n", grid.render(|_, symbol| symbol.copied().unwrap_or('.')));
+    }
 }
@@ -1,12 +1,16 @@
 #![allow(dead_code)]
 #![allow(refining_impl_trait)]
 
+use aoc_input::{GridError, normalized};
 use clap::Parser;
 use std::borrow::Cow;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Cursor, Error as IoError, ErrorKind, Read};
+use std::io::{BufRead, Cursor, Error as IoError, ErrorKind, Read};
 use std::path::PathBuf;
 use std::sync::OnceLock;
+use thiserror::Error;
+
+pub use aoc_input::{lines, normalize};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -39,10 +43,41 @@ macro_rules! input {
     }};
 }
 
+#[derive(Debug, Error, PartialEq)]
+pub enum InputError {
+    #[error("Expected a grid, but the input is empty")]
+    EmptyGrid,
+    #[error("Expected every row of the grid to be {expected} characters wide like the first, but row {row} is {found}")]
+    RaggedGrid { row: usize, expected: usize, found: usize },
+}
+
+/// Inputs are normalised (see [`normalize`]) as they're loaded, whether from
+/// a file or a string, so that solutions see the same text either way.
 pub trait Input {
     fn into_buffer(self) -> impl BufRead;
     fn into_string(self) -> String;
     fn as_str(&self) -> &str;
+
+    /// Every line from the first to the last that isn't blank, without any
+    /// trailing whitespace.
+    fn lines(&self) -> impl Iterator<Item = &str> {
+        lines(self.as_str())
+    }
+
+    /// Groups of lines separated by one or more blank lines, as is common
+    /// when an input has more than one section.
+    fn paragraphs(&self) -> impl Iterator<Item = &str> {
+        aoc_input::paragraphs(self.as_str())
+    }
+
+    /// Every row of a rectangular grid of characters, or an error naming the
+    /// first row (counting from one) that isn't as wide as the first one.
+    fn grid(&self) -> Result<Vec<&str>, InputError> {
+        aoc_input::grid(self.as_str()).map_err(|err| match err {
+            GridError::Empty => InputError::EmptyGrid,
+            GridError::Ragged { row, expected, found, .. } => InputError::RaggedGrid { row, expected, found },
+        })
+    }
 }
 
 pub struct RawInput<'a> {
@@ -50,7 +85,11 @@ pub struct RawInput<'a> {
 }
 impl<'a> RawInput<'a> {
     pub fn new(s: impl Into<Cow<'a, str>>) -> Self {
-        Self { contents: s.into() }
+        let contents = match s.into() {
+            Cow::Borrowed(contents) => normalize(contents),
+            Cow::Owned(contents) => Cow::Owned(normalized(contents)),
+        };
+        Self { contents }
     }
 }
 impl<'a> Input for RawInput<'a> {
@@ -124,12 +163,13 @@ impl FileInput {
             .expect("Could not clone reference to known file")
             .read_to_string(&mut buffer)
             .expect("Failed to read contents of input file");
-        buffer
+        normalized(buffer)
     }
 }
 impl Input for FileInput {
-    fn into_buffer(self) -> BufReader<File> {
-        BufReader::new(self.file)
+    /// The file is read in full, so that it gets normalised like any other input.
+    fn into_buffer(self) -> Cursor<String> {
+        Cursor::new(self.into_string())
     }
 
     fn into_string(mut self) -> String {
@@ -143,3 +183,26 @@ impl Input for FileInput {
         self.contents.get_or_init(|| self.load())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("ab\ncd\n"), Cow::Borrowed("ab\ncd\n")));
+        assert_eq!("ab\ncd\n", normalize("\u{feff}ab\r\ncd\r\n\r\n  \n"));
+        assert_eq!("  ab\ncd  \n", RawInput::new("  ab\ncd  \n\n".to_string()).as_str());
+        assert_eq!("", normalize("\n \n"));
+    }
+
+    #[test]
+    fn test_views() {
+        let input = RawInput::new("\n  ab  \ncd\n\n \nef\n");
+        assert_eq!(vec!["  ab", "cd", "", "", "ef"], input.lines().collect::<Vec<_>>());
+        assert_eq!(vec!["  ab  \ncd", "ef"], input.paragraphs().collect::<Vec<_>>());
+        assert_eq!(Ok(vec!["abc", "def"]), RawInput::new("abc\ndef\n").grid());
+        assert_eq!(Err(InputError::RaggedGrid { row: 2, expected: 2, found: 3 }), RawInput::new("ab\ncde").grid());
+        assert_eq!(Err(InputError::EmptyGrid), RawInput::new("\n").grid());
+    }
+}
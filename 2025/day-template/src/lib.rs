@@ -0,0 +1,34 @@
+use common::Solution;
+
+#[derive(Debug)]
+pub enum Error {
+    NotYetImplemented,
+}
+
+pub struct DayXX {}
+impl Solution for DayXX {
+    type Error = Error;
+    fn parse(_input: impl common::Input) -> Result<Self, Self::Error> {
+        Ok(Self {})
+    }
+
+    fn part1(&self) -> Result<String, Self::Error> {
+        Err(Error::NotYetImplemented)
+    }
+    fn part2(&self) -> Result<String, Self::Error> {
+        Err(Error::NotYetImplemented)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use common::input::RawInput;
+    const TEST_INPUT: &str = r#""#;
+
+    #[test]
+    fn test_parse() {
+        let input = RawInput::new(TEST_INPUT);
+        assert!(DayXX::parse(input).is_ok());
+    }
+}
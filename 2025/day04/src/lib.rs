@@ -1,5 +1,7 @@
 use common::Solution;
 use common::grid::{Grid, Position};
+use std::collections::BTreeSet;
+use std::iter;
 
 #[derive(Debug)]
 pub enum Error {
@@ -27,6 +29,36 @@ impl Day04 {
         }
     }
 
+    /// Each wave of removals in part 2, marking the rolls about to be removed
+    /// with an `x`.
+    pub fn frames(&self) -> impl Iterator<Item = String> + use<> {
+        let mut grid = self.grid.clone();
+        let (mut waves, mut removed, mut finished) = (0, 0, false);
+        iter::from_fn(move || {
+            if finished {
+                return None;
+            }
+            let removable: BTreeSet<Position> =
+                Self::get_removable(&grid, Self::AT_MOST).into_iter().map(|(position, _item)| *position).collect();
+            finished = removable.is_empty();
+            let frame = grid.render(|position, item| match item {
+                Some(Item::PaperRoll) if removable.contains(position) => 'x',
+                Some(Item::PaperRoll) => '@',
+                Some(Item::Empty) | None => '.',
+            });
+            let caption = match finished {
+                true => format!("Removed {removed} rolls, and none can be removed any more"),
+                false => format!("Wave {}: removing {} rolls ({removed} removed so far)", waves + 1, removable.len()),
+            };
+            for position in &removable {
+                grid.remove(position);
+            }
+            waves += 1;
+            removed += removable.len();
+            Some(format!("{caption}\n{frame}"))
+        })
+    }
+
     fn get_removable(grid: &Grid<Item>, surrounded_at_most: usize) -> Vec<(&Position, &Item)> {
         grid.iter()
             .filter(|(position, item)| {
@@ -106,4 +138,12 @@ mod test {
         let solution = Day04::parse(input).unwrap();
         assert_eq!("43", solution.part2().unwrap());
     }
+
+    #[test]
+    fn test_frames() {
+        let input = RawInput::new(TEST_INPUT);
+        let frames: Vec<String> = Day04::parse(input).unwrap().frames().collect();
+        assert!(frames[0].starts_with("Wave 1: removing 13 rolls (0 removed so far)\n..xx.xx@x.\n"));
+        assert!(frames.last().unwrap().starts_with("Removed 43 rolls"));
+    }
 }
@@ -0,0 +1,142 @@
+use aoc_common::visualize::{Frames, Visualize};
+use aoc_common::{AocError, ParseError, RunnerError, Solution};
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::marker::PhantomData;
+
+type Part = Box<dyn Fn(&str) -> Result<String, RunnerError> + Send + Sync>;
+type Render = Box<dyn Fn(&str) -> Result<Frames, AocError> + Send + Sync>;
+
+/// Adapter for the 2023 solutions, where each part is a standalone
+/// `process(&str)` function that does its own parsing. The parse phase only
+/// takes a copy of the input, so parsing gets timed as part of each part.
+pub struct Process {
+    part1: Part,
+    part2: Part,
+}
+impl Process {
+    pub fn new<E1, E2>(part1: fn(&str) -> Result<String, E1>, part2: fn(&str) -> Result<String, E2>) -> Self
+    where
+        E1: Error + Send + Sync + 'static,
+        E2: Error + Send + Sync + 'static,
+    {
+        Self { part1: Self::part(part1), part2: Self::part(part2) }
+    }
+
+    /// For the last day of the year, which only ever has one part.
+    pub fn part_one_only<E>(part1: fn(&str) -> Result<String, E>) -> Self
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Self {
+            part1: Self::part(part1),
+            part2: Box::new(|_| Err(RunnerError::Unimplemented)),
+        }
+    }
+
+    fn part<E>(process: fn(&str) -> Result<String, E>) -> Part
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        Box::new(move |input| process(input).map_err(|err| RunnerError::Other(Box::new(err))))
+    }
+}
+impl Solution for Process {
+    type Parsed = String;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        Ok(input.to_string())
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        (self.part1)(input)
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        (self.part2)(input)
+    }
+}
+
+/// Adapter for the 2025 solutions, which parse themselves into `Self` and
+/// only report their errors through `Debug`.
+pub struct Modern<S>(PhantomData<fn() -> S>);
+impl<S> Default for Modern<S> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+impl<S> Solution for Modern<S>
+where
+    S: aoc_2025_common::Solution + Send + Sync,
+    S::Error: Debug,
+{
+    type Parsed = S;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+        S::parse(aoc_2025_common::input::RawInput::new(input)).map_err(|err| ParseError::Custom(format!("{err:?}")))
+    }
+
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        input.part1().map_err(|err| RunnerError::Custom(format!("{err:?}")))
+    }
+
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+        input.part2().map_err(|err| RunnerError::Custom(format!("{err:?}")))
+    }
+}
+
+/// Adapter for the visualizations of the other years, which are plain
+/// functions rendering each step of a simulation for an input.
+pub struct Animation(Render);
+impl Animation {
+    pub fn new<I, E>(frames: fn(&str) -> Result<I, E>) -> Self
+    where
+        I: Iterator<Item = String> + 'static,
+        E: Debug + 'static,
+    {
+        Self(Box::new(move |input| match frames(input) {
+            Ok(frames) => Ok(Box::new(frames) as Frames),
+            Err(err) => Err(AocError::Parse(ParseError::Custom(format!("{err:?}")))),
+        }))
+    }
+}
+impl Visualize for Animation {
+    fn frames(&self, input: &str) -> Result<Frames, AocError> {
+        (self.0)(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process() {
+        let solution = Process::part_one_only(aoc_2023_day01::part1::process);
+        let parsed = solution.parse("1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet").unwrap();
+        assert_eq!("142", solution.part1(&parsed).unwrap().to_string());
+        assert!(matches!(solution.part2(&parsed), Err(RunnerError::Unimplemented)));
+    }
+
+    #[test]
+    fn test_modern() {
+        let solution = Modern::<aoc_2025_day04::Day04>::default();
+        let parsed = solution.parse("..@@.@@@@.\n@@@.@.@.@@\n@@@@@.@.@@\n@.@@@@..@.\n@@.@@@@.@@\n.@@@@@@@.@\n.@.@.@.@@@\n@.@@@.@@@@\n.@@@@@@@@.\n@.@.@@@.@.").unwrap();
+        assert_eq!("13", solution.part1(&parsed).unwrap().to_string());
+        assert_eq!("43", solution.part2(&parsed).unwrap().to_string());
+        assert!(matches!(solution.parse("@?"), Err(ParseError::Custom(_))));
+    }
+
+    #[test]
+    fn test_animation() {
+        let animation = Animation::new(aoc_2023_day16::frames);
+        let frames: Vec<String> = animation.frames(".\\\n./").unwrap().collect();
+        assert_eq!(vec!["Step 0: 0 tiles energized\n>\\\n./\n", "Step 1: 1 tiles energized\n#>\n./\n"], frames[..2]);
+        assert_eq!("Step 4: 4 tiles energized\n#\\\n#/\n", frames.last().unwrap());
+        assert!(matches!(animation.frames(".\n.."), Err(AocError::Parse(_))));
+
+        let frames: Vec<String> = Animation::new(aoc_2023_day14::frames).frames("O\n.").unwrap().collect();
+        assert_eq!(vec!["Initial platform, load 2\nO\n.\n", "Spin 1, tilted north, load 2\nO\n.\n"], frames[..2]);
+        assert_eq!(9, frames.len());
+    }
+}
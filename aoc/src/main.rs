@@ -0,0 +1,12 @@
+mod adapters;
+mod y2023;
+mod y2025;
+
+use aoc_common::app::MultiYear as Application;
+use std::process::exit;
+
+fn main() -> ! {
+    let code =
+        Application::new([(2023, y2023::solutions()), (2024, aoc_2024::solutions()), (2025, y2025::solutions())]).run();
+    exit(code);
+}
@@ -0,0 +1,29 @@
+use crate::adapters::{Animation, Process};
+use aoc_common::register_days;
+use aoc_common::registry::Registry;
+
+/// Part 2 of day 14 spins the platform a billion times.
+const SPIN_CYCLES: usize = 1_000_000_000;
+
+pub fn solutions() -> Registry {
+    let mut registry = register_days! {
+        1 => Process::new(aoc_2023_day01::part1::process, aoc_2023_day01::part2::process),
+        2 => Process::new(aoc_2023_day02::part1::process, aoc_2023_day02::part2::process),
+        3 => Process::new(aoc_2023_day03::part1::process, aoc_2023_day03::part2::process),
+        4 => Process::new(aoc_2023_day04::part1::process, aoc_2023_day04::part2::process),
+        5 => Process::new(aoc_2023_day05::part1::process, aoc_2023_day05::part2::process),
+        6 => Process::new(aoc_2023_day06::part1::process, aoc_2023_day06::part2::process),
+        7 => Process::new(aoc_2023_day07::part1::process, aoc_2023_day07::part2::process),
+        8 => Process::new(aoc_2023_day08::part1::process, aoc_2023_day08::part2::process),
+        9 => Process::new(aoc_2023_day09::part1::process, aoc_2023_day09::part2::process),
+        10 => Process::new(aoc_2023_day10::part1::process, aoc_2023_day10::part2::process),
+        11 => Process::new(aoc_2023_day11::part1::process, aoc_2023_day11::part2::process),
+        14 => Process::new(aoc_2023_day14::part1::process, |input| aoc_2023_day14::part2::process(input, SPIN_CYCLES)),
+        15 => Process::new(aoc_2023_day15::part1::process, aoc_2023_day15::part2::process),
+        16 => Process::new(aoc_2023_day16::part1::process, aoc_2023_day16::part2::process),
+        25 => Process::part_one_only(aoc_2023_day25::part1::process),
+    };
+    registry.register_visualization(14, Animation::new(aoc_2023_day14::frames));
+    registry.register_visualization(16, Animation::new(aoc_2023_day16::frames));
+    registry
+}
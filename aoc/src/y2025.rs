@@ -0,0 +1,59 @@
+use crate::adapters::{Animation, Modern, Process};
+use aoc_2025_common::input::RawInput;
+use aoc_2025_common::Solution;
+use aoc_common::register_days;
+use aoc_common::registry::Registry;
+use aoc_common::RunnerError;
+use std::convert::Infallible;
+
+// The first few days predate `common::Solution`, and only expose the
+// building blocks that their `main.rs` puts together.
+
+fn day01_part1(input: &str) -> Result<String, Infallible> {
+    let instructions: aoc_2025_day01::models::InstructionSet = input.into();
+    let spins = instructions.spin(aoc_2025_day01::STARTING_POSITION);
+    Ok(aoc_2025_day01::count_zeros_landed(&spins).to_string())
+}
+
+fn day01_part2(input: &str) -> Result<String, Infallible> {
+    let instructions: aoc_2025_day01::models::InstructionSet = input.into();
+    let spins = instructions.spin(aoc_2025_day01::STARTING_POSITION);
+    Ok(aoc_2025_day01::count_zeros_touched(&spins).to_string())
+}
+
+fn day02_part1(input: &str) -> Result<String, Infallible> {
+    let pairs = aoc_2025_day02::parse(input);
+    Ok(pairs.iter().flat_map(|pair| pair.repeated_twice_ids()).sum::<u64>().to_string())
+}
+
+fn day02_part2(input: &str) -> Result<String, Infallible> {
+    let pairs = aoc_2025_day02::parse(input);
+    Ok(pairs.iter().flat_map(|pair| pair.repeated_any_ids()).sum::<u64>().to_string())
+}
+
+fn day03(input: &str, digits: usize) -> Result<String, RunnerError> {
+    let debug = |err: aoc_2025_day03::Error| RunnerError::Custom(format!("{err:?}"));
+    let banks = aoc_2025_day03::parse_banks(input).map_err(debug)?;
+    let joltages = banks
+        .iter()
+        .map(|bank| aoc_2025_day03::largest_number_with_digits(bank, digits))
+        .collect::<Result<Vec<u64>, _>>()
+        .map_err(debug)?;
+    Ok(joltages.iter().sum::<u64>().to_string())
+}
+
+pub fn solutions() -> Registry {
+    let mut registry = register_days! {
+        1 => Process::new(day01_part1, day01_part2),
+        2 => Process::new(day02_part1, day02_part2),
+        3 => Process::new(|input| day03(input, 2), |input| day03(input, 12)),
+        4 => Modern::<aoc_2025_day04::Day04>::default(),
+        5 => Modern::<aoc_2025_day05::Day05>::default(),
+        6 => Modern::<aoc_2025_day06::Day06>::default(),
+    };
+    registry.register_visualization(
+        4,
+        Animation::new(|input| aoc_2025_day04::Day04::parse(RawInput::new(input)).map(|day| day.frames())),
+    );
+    registry
+}
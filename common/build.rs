@@ -0,0 +1,39 @@
+//! Record what the runner is being built from, so that timings are kept
+//! against the build that produced them rather than whatever happens to be
+//! checked out (if anything) when they're recorded.
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok().filter(|output| output.status.success())?;
+    let output = String::from_utf8(output.stdout).ok()?;
+    Some(output.trim().to_string()).filter(|output| !output.is_empty())
+}
+
+fn main() {
+    if let Some(commit) = git(&["describe", "--always", "--dirty"]) {
+        println!("cargo:rustc-env=AOC_BUILD_COMMIT={commit}");
+    }
+    // Build scripts are only told whether a profile is based on `dev` or
+    // `release`, but every profile gets its own directory of artifacts:
+    // `target/{profile}/build/{package}/out`.
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("Cargo sets OUT_DIR for build scripts"));
+    let profile = out_dir
+        .ancestors()
+        .nth(3)
+        .and_then(Path::file_name)
+        .and_then(|name| name.to_str())
+        .map(String::from)
+        .unwrap_or_else(|| env::var("PROFILE").expect("Cargo sets PROFILE for build scripts"));
+    println!("cargo:rustc-env=AOC_BUILD_PROFILE={profile}");
+    // Describe the commit again whenever a different one gets checked out, or
+    // files get staged.
+    if let Some(git_dir) = git(&["rev-parse", "--git-dir"]) {
+        for path in ["HEAD", "index", "refs", "packed-refs"].map(|path| Path::new(&git_dir).join(path)) {
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! Normalising puzzle inputs, and splitting them up in the ways that puzzles
+//! commonly need. Shared by the runners of every year, so that a solution sees
+//! the same text whichever runner loaded its input.
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Byte order mark that some editors put at the start of a file.
+const BOM: char = '\u{feff}';
+
+/// Normalise an input the same way wherever it was loaded from: without a
+/// byte order mark, with Unix line endings, and without any blank lines at
+/// the end (a final newline is kept, if there was one). Leading whitespace
+/// is left alone, as it can be part of the puzzle.
+pub fn normalize(input: &str) -> Cow<'_, str> {
+    let input = input.strip_prefix(BOM).unwrap_or(input);
+    let mut input = match input.contains('\r') {
+        true => Cow::Owned(input.replace("\r\n", "\n")),
+        false => Cow::Borrowed(input),
+    };
+    let content = input.trim_end().len();
+    let end = match input[content..].find('\n') {
+        Some(newline) if content > 0 => content + newline + 1,
+        Some(_) => 0,
+        None => input.len(),
+    };
+    match &mut input {
+        Cow::Borrowed(borrowed) => *borrowed = &borrowed[..end],
+        Cow::Owned(owned) => owned.truncate(end),
+    }
+    input
+}
+
+/// [`normalize`] an input that has already been read in full, only making
+/// a copy of it when something had to change.
+pub fn normalized(input: String) -> String {
+    match normalize(&input) {
+        Cow::Borrowed(normal) if normal.len() == input.len() => input,
+        normal => normal.into_owned(),
+    }
+}
+
+/// Where the lines of an input that aren't blank start and end: from the
+/// start of the first line that isn't blank, to the end of the last one.
+fn body(input: &str) -> (usize, &str) {
+    let first = input.len() - input.trim_start().len();
+    let start = input[..first].rfind('\n').map_or(0, |newline| newline + 1);
+    (start, &input[start..input.trim_end().len().max(start)])
+}
+
+/// Every line from the first to the last that isn't blank, without any
+/// trailing whitespace.
+pub fn lines(input: &str) -> impl Iterator<Item = &str> {
+    body(input).1.lines().map(str::trim_end)
+}
+
+/// Groups of lines separated by one or more blank lines, as is common when
+/// an input has more than one section. Only the end of each group is
+/// trimmed; its other lines are left as they are.
+pub fn paragraphs(input: &str) -> impl Iterator<Item = &str> {
+    let (_, mut rest) = body(input);
+    std::iter::from_fn(move || {
+        // Skip over the blank lines between paragraphs.
+        while let Some(line) = rest.split_inclusive('\n').next().filter(|line| line.trim().is_empty()) {
+            rest = &rest[line.len()..];
+        }
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest.split_inclusive('\n').take_while(|line| !line.trim().is_empty()).map(str::len).sum();
+        let (paragraph, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(paragraph.trim_end())
+    })
+}
+
+/// Why an input isn't a rectangular grid of characters.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GridError {
+    Empty,
+    Ragged {
+        /// Which row (counting from one) isn't as wide as the first.
+        row: usize,
+        /// Where that row starts in the input, in bytes.
+        offset: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+impl Display for GridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Empty => write!(f, "Expected a grid, but the input is empty"),
+            Self::Ragged { expected, found, .. } => {
+                write!(f, "Expected a row of {expected} characters like the first, but found {found}")
+            },
+        }
+    }
+}
+impl Error for GridError {}
+
+/// Every line of a rectangular grid of characters, or an error describing
+/// the first line that isn't as wide as the first one.
+pub fn grid(input: &str) -> Result<Vec<&str>, GridError> {
+    let (mut offset, body) = body(input);
+    let mut rows: Vec<&str> = Vec::new();
+    for line in body.split_inclusive('\n') {
+        let row = line.trim_end();
+        if let Some(first) = rows.first() {
+            let (expected, found) = (first.chars().count(), row.chars().count());
+            if found != expected {
+                return Err(GridError::Ragged { row: rows.len() + 1, offset, expected, found });
+            }
+        }
+        rows.push(row);
+        offset += line.len();
+    }
+    match rows.is_empty() {
+        true => Err(GridError::Empty),
+        false => Ok(rows),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert!(matches!(normalize("ab\ncd\n"), Cow::Borrowed("ab\ncd\n")));
+        assert_eq!("ab\ncd\n", normalize("\u{feff}ab\r\ncd\r\n\r\n  \n"));
+        assert_eq!("ab\ncd  \n", normalize("ab\ncd  \n\n"));
+        assert_eq!("  ab\ncd", normalize("  ab\ncd"));
+        assert_eq!("", normalize("\n \n"));
+        assert_eq!("ab\n", normalized("ab\n\n".to_string()));
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(vec!["  ab", "", "cd"], lines("\n\n  ab  \n\t\ncd\n\n").collect::<Vec<_>>());
+        assert_eq!(0, lines(" \n ").count());
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let paragraphs: Vec<&str> = paragraphs("\nab  \ncd\n\n \n  ef\n\ngh\n").collect();
+        assert_eq!(vec!["ab  \ncd", "  ef", "gh"], paragraphs);
+    }
+
+    #[test]
+    fn test_grid() {
+        assert_eq!(Ok(vec!["ab", "cd"]), grid("\nab\ncd  \n\n"));
+        let ragged = GridError::Ragged { row: 3, offset: 8, expected: 3, found: 2 };
+        assert_eq!(Err(ragged), grid("abc\ndef\ngh\n"));
+        assert!(grid("ab\n\ncd").is_err());
+        assert_eq!(Err(GridError::Empty), grid("\n"));
+    }
+}
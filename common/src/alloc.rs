@@ -0,0 +1,166 @@
+use humanize_bytes::humanize_bytes_binary;
+use serde::Serialize;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Heap usage of a single phase (loading, parsing or running a part).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Allocations {
+    /// Number of allocations, counting each reallocation as a new allocation.
+    pub count: u64,
+    /// Total bytes requested by those allocations.
+    pub bytes: u64,
+    /// Highest number of bytes live at once during the phase, above what was
+    /// already live when the phase started.
+    pub peak: u64,
+}
+impl Display for Allocations {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{} allocations, {} allocated, {} peak",
+            self.count,
+            humanize_bytes_binary!(self.bytes),
+            humanize_bytes_binary!(self.peak),
+        )
+    }
+}
+
+/// Run a phase and count the allocations it makes. Counting needs the
+/// `count-allocations` feature (which installs [`CountingAllocator`] as the
+/// global allocator); without it there's nothing to report.
+///
+/// Only allocations made on the current thread are counted, so that phases
+/// running on other threads at the same time don't get mixed in.
+pub fn measure<T>(phase: impl FnOnce() -> T) -> (T, Option<Allocations>) {
+    #[cfg(feature = "count-allocations")]
+    {
+        let start = counting::reset_peak();
+        let result = phase();
+        (result, Some(counting::since(start)))
+    }
+    #[cfg(not(feature = "count-allocations"))]
+    {
+        (phase(), None)
+    }
+}
+
+#[cfg(feature = "count-allocations")]
+pub use counting::CountingAllocator;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[cfg(feature = "count-allocations")]
+mod counting {
+    use super::Allocations;
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::Cell;
+
+    #[derive(Clone, Copy)]
+    pub(super) struct Counters {
+        count: u64,
+        bytes: u64,
+        live: i64,
+        peak: i64,
+    }
+
+    thread_local! {
+        // Const-initialised without a destructor, so that accessing it never
+        // allocates (which would recurse back into the allocator).
+        static COUNTERS: Cell<Counters> = const { Cell::new(Counters { count: 0, bytes: 0, live: 0, peak: 0 }) };
+    }
+
+    fn update(change: impl FnOnce(&mut Counters)) -> Option<Counters> {
+        // Fails while the thread is being torn down; those allocations don't
+        // belong to any phase anyway.
+        COUNTERS
+            .try_with(|cell| {
+                let mut counters = cell.get();
+                change(&mut counters);
+                cell.set(counters);
+                counters
+            })
+            .ok()
+    }
+
+    fn allocated(size: usize) {
+        update(|counters| {
+            counters.count += 1;
+            counters.bytes += size as u64;
+            counters.live += size as i64;
+            counters.peak = counters.peak.max(counters.live);
+        });
+    }
+
+    fn freed(size: usize) {
+        update(|counters| counters.live -= size as i64);
+    }
+
+    pub(super) fn reset_peak() -> Counters {
+        update(|counters| counters.peak = counters.live).expect("Counting allocations outside of a thread")
+    }
+
+    pub(super) fn since(start: Counters) -> Allocations {
+        let end = update(|_| {}).expect("Counting allocations outside of a thread");
+        Allocations {
+            count: end.count - start.count,
+            bytes: end.bytes - start.bytes,
+            peak: (end.peak - start.live).max(0) as u64,
+        }
+    }
+
+    /// Wraps the system allocator, counting every allocation made by the
+    /// current thread.
+    pub struct CountingAllocator;
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            freed(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new = System.realloc(ptr, layout, new_size);
+            if !new.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            new
+        }
+    }
+}
+
+#[cfg(all(test, feature = "count-allocations"))]
+mod tests {
+    use super::*;
+    use std::hint::black_box;
+
+    #[test]
+    fn test_measure() {
+        let (_, allocations) = measure(|| {
+            let first = black_box(vec![0u8; 1024]);
+            drop(first);
+            black_box(vec![0u8; 512])
+        });
+        let allocations = allocations.unwrap();
+        assert_eq!(2, allocations.count);
+        assert_eq!(1536, allocations.bytes);
+        assert_eq!(1024, allocations.peak);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{AocError, Chain, DayResult, RunResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Previously accepted answers for a single day.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct DayAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part1: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part2: Option<String>,
+}
+
+/// Known answers for a whole year, stored next to the inputs as
+/// `input/{year}/answers.toml` and keyed by zero-padded day number:
+///
+/// ```toml
+/// [01]
+/// part1 = "11"
+/// part2 = "31"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct Answers {
+    days: BTreeMap<String, DayAnswers>,
+}
+impl Answers {
+    pub fn path(year: u16) -> PathBuf {
+        PathBuf::from(format!("input/{}/answers.toml", year))
+    }
+
+    /// Load the answers for a year; a year without an answers file simply
+    /// has no known answers yet.
+    pub fn load(year: u16) -> Result<Self, AocError> {
+        match fs::read_to_string(Self::path(year)) {
+            Ok(contents) => Self::from_toml(&contents),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(AocError::Io(err)),
+        }
+    }
+
+    /// Load the answers to check results against. Answers that can't be read
+    /// only leave every verdict unchecked, rather than failing every day.
+    pub fn load_or_unchecked(year: u16) -> Self {
+        Self::load(year).unwrap_or_else(|err| {
+            eprintln!("Not checking against the known answers: {}", Chain(&err));
+            Self::default()
+        })
+    }
+
+    pub fn from_toml(contents: &str) -> Result<Self, AocError> {
+        toml::from_str(contents).map_err(|err| AocError::Answers(err.to_string()))
+    }
+
+    pub fn save(&self, year: u16) -> Result<(), AocError> {
+        let contents = toml::to_string(self).map_err(|err| AocError::Answers(err.to_string()))?;
+        let path = Self::path(year);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u8) -> Option<&DayAnswers> {
+        self.days.get(&format!("{:02}", day))
+    }
+
+    /// Record an answer for a part, as long as one isn't already known.
+    /// Returns whether it was recorded.
+    pub fn accept(&mut self, day: u8, part: u8, answer: &str) -> bool {
+        let entry = self.days.entry(format!("{:02}", day)).or_default();
+        let known = if part == 1 { &mut entry.part1 } else { &mut entry.part2 };
+        match known {
+            Some(_) => false,
+            None => {
+                *known = Some(answer.to_owned());
+                true
+            },
+        }
+    }
+
+    /// Record any successful answers that aren't already known. Answers that
+    /// are already known are never overwritten, otherwise recording would
+    /// silently accept a regression. Returns whether anything new was recorded.
+    pub fn record(&mut self, day: u8, result: &DayResult) -> bool {
+        if result.input.is_custom() {
+            return false;
+        }
+        let mut recorded = false;
+        for (part, result) in [(1, &result.part_one), (2, &result.part_two)] {
+            if let RunResult::Success(answer) = &result.answer {
+                recorded |= self.accept(day, part, answer);
+            }
+        }
+        recorded
+    }
+}
+
+/// How an answer compares against the previously accepted answer.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Incorrect {
+        expected: String,
+    },
+    #[default]
+    Unknown,
+}
+impl Verdict {
+    pub(crate) fn check(expected: Option<&String>, answer: &RunResult) -> Self {
+        match (expected, answer) {
+            (None, _) | (_, RunResult::Skipped) => Self::Unknown,
+            (Some(expected), RunResult::Success(actual)) if expected == actual => Self::Correct,
+            // A part that errors when it used to produce an answer is just as
+            // much a regression as a part that produces the wrong answer.
+            (Some(expected), _) => Self::Incorrect { expected: expected.to_owned() },
+        }
+    }
+
+    pub fn is_incorrect(&self) -> bool {
+        matches!(self, Self::Incorrect { .. })
+    }
+}
+impl Display for Verdict {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Correct => write!(f, "✅ Correct"),
+            Self::Incorrect { expected } => write!(f, "❌ Incorrect (expected {expected})"),
+            Self::Unknown => write!(f, "❔ Unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+[01]
+part1 = "11"
+part2 = "31"
+
+[03]
+part1 = "161"
+"#;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = Answers::from_toml(ANSWERS).unwrap();
+        assert_eq!(
+            Some(&DayAnswers {
+                part1: Some("11".to_string()),
+                part2: Some("31".to_string())
+            }),
+            answers.get(1)
+        );
+        assert_eq!(Some(&DayAnswers { part1: Some("161".to_string()), part2: None }), answers.get(3));
+        assert_eq!(None, answers.get(2));
+    }
+
+    #[test]
+    fn test_answers_round_trip() {
+        let answers = Answers::from_toml(ANSWERS).unwrap();
+        let serialized = toml::to_string(&answers).unwrap();
+        assert_eq!(answers, Answers::from_toml(&serialized).unwrap());
+    }
+
+    #[test]
+    fn test_record_ignores_custom_input() {
+        let result = |input: crate::app::Input| DayResult {
+            input,
+            parse_time: Default::default(),
+            parse_allocations: None,
+            part_one: crate::PartResult::new((RunResult::Success("7".to_string()), Default::default(), None), None),
+            part_two: crate::PartResult::new((RunResult::Skipped, Default::default(), None), None),
+        };
+        let input = || crate::app::Input::new(2024, 1, "example.txt".to_string(), String::new(), Default::default());
+        let mut answers = Answers::default();
+        assert!(!answers.record(1, &result(input().custom())));
+        assert_eq!(None, answers.get(1));
+        assert!(answers.record(1, &result(input())));
+        assert_eq!(Some(&DayAnswers { part1: Some("7".to_string()), part2: None }), answers.get(1));
+    }
+
+    #[test]
+    fn test_verdict() {
+        let expected = "42".to_string();
+        let success = |answer: &str| RunResult::Success(answer.to_string());
+        assert_eq!(Verdict::Unknown, Verdict::check(None, &success("42")));
+        assert_eq!(Verdict::Correct, Verdict::check(Some(&expected), &success("42")));
+        assert_eq!(Verdict::Incorrect { expected: "42".to_string() }, Verdict::check(Some(&expected), &success("24")));
+        assert!(Verdict::check(Some(&expected), &RunResult::Fail(crate::RunnerError::Unimplemented)).is_incorrect());
+        assert_eq!(Verdict::Unknown, Verdict::check(Some(&expected), &RunResult::Skipped));
+    }
+}
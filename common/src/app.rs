@@ -1,47 +1,287 @@
-use std::{fmt::Display, time::Duration};
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs::File,
+    io::BufWriter,
+    ops::RangeInclusive,
+    panic::{self, AssertUnwindSafe},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
 
-use crate::{AdventOfCode, AocError, DayResult};
-use clap::{Arg, Command};
+use crate::alloc::Allocations;
+use crate::bench::BenchResult;
+use crate::download::DownloadConfig;
+use crate::history::{Build, History, Phase, Record};
+use crate::registry::Registry;
+use crate::report::{self, DayReport, Format};
+use crate::scaffold::Scaffold;
+use crate::submit::{Feedback, Outcome, Submission, Submissions, Submitter};
+use crate::visualize::{self, GraphFormat, Player};
+use crate::{AdventOfCode, Answers, AocError, Chain, DayResult, PartResult, Parts, RunResult, Verdict};
+use clap::{error::ErrorKind, Arg, ArgAction, ArgMatches, Command};
 use humanize_bytes::humanize_bytes_binary;
 use humanize_duration::{prelude::DurationExt, Truncate};
 
 type Response = Result<DayResult, AocError>;
 
+/// How many seconds a part gets before it's given up on, unless told otherwise.
+const DEFAULT_TIMEOUT: &str = "60";
+
+/// Exit code when the runner couldn't produce a result, or an answer regressed.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code when the input for a single day could not be found.
+pub const EXIT_NO_INPUT: i32 = 2;
+/// Exit code flag set when part 1 of any day failed.
+pub const EXIT_PART_ONE_FAILED: i32 = 4;
+/// Exit code flag set when part 2 of any day failed.
+pub const EXIT_PART_TWO_FAILED: i32 = 8;
+
 pub struct Input {
+    year: u16,
+    day: u8,
+    custom: bool,
     filepath: String,
     contents: String,
     elapsed: Duration,
+    allocations: Option<Allocations>,
     length: usize,
 }
 impl Input {
-    pub(crate) fn new(filepath: String, contents: String, elapsed: Duration) -> Self {
+    pub(crate) fn new(year: u16, day: u8, filepath: String, contents: String, elapsed: Duration) -> Self {
         Self {
+            year,
+            day,
+            custom: false,
             filepath,
             length: contents.len(),
             contents,
             elapsed,
+            allocations: None,
         }
     }
 
+    /// Attach the allocations made while loading the input, if counted.
+    pub(crate) fn allocated(mut self, allocations: Option<Allocations>) -> Self {
+        self.allocations = allocations;
+        self
+    }
+
+    /// Mark the input as not being the puzzle input for the day.
+    pub(crate) fn custom(mut self) -> Self {
+        self.custom = true;
+        self
+    }
+
+    pub(crate) fn is_custom(&self) -> bool {
+        self.custom
+    }
+
+    pub(crate) fn year(&self) -> u16 {
+        self.year
+    }
+
+    pub(crate) fn day(&self) -> u8 {
+        self.day
+    }
+
     pub(crate) fn contents(&self) -> &str {
         &self.contents
     }
+
+    pub(crate) fn filepath(&self) -> &str {
+        &self.filepath
+    }
+
+    pub(crate) fn length(&self) -> usize {
+        self.length
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub(crate) fn allocations(&self) -> Option<Allocations> {
+        self.allocations
+    }
 }
 impl Display for Input {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
         write!(
             f,
-            r#"Input ({}) loaded from disk in {}.
+            r#"Input ({}) loaded in {}.
 Length: {}"#,
             self.filepath,
-            self.elapsed.human(Truncate::Nano),
+            crate::usage(self.elapsed, self.allocations),
             humanize_bytes_binary!(self.length),
         )
     }
 }
 
+/// Which day(s) the runner was asked to run.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Selection {
+    Day(u8),
+    Range(RangeInclusive<u8>),
+    All,
+}
+impl Selection {
+    fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("all") {
+            return Ok(Self::All);
+        }
+        let day = |s: &str| s.trim().parse::<u8>().map_err(|_| format!("`{s}` is not a valid day number"));
+        let range = match (value.split_once("..="), value.split_once("..")) {
+            (Some((start, end)), _) => Some((day(start)?, day(end)?)),
+            (None, Some((start, end))) => Some((day(start)?, day(end)?.saturating_sub(1))),
+            (None, None) => None,
+        };
+        match range {
+            Some((start, end)) if start > end => Err(format!("`{value}` is an empty range of days")),
+            Some((start, end)) => Ok(Self::Range(start..=end)),
+            None => day(value).map(Self::Day),
+        }
+    }
+}
+
+/// A single row in the summary table printed after running more than one day.
+struct SummaryRow {
+    day: u8,
+    result: Response,
+}
+struct Summary {
+    rows: Vec<SummaryRow>,
+}
+impl Summary {
+    const HEADINGS: [&'static str; 7] =
+        ["Day", "Part 1", "Part 2", "Parse Time", "Part 1 Time", "Part 2 Time", "Status"];
+
+    fn exit_code(&self) -> i32 {
+        self.rows.iter().fold(0, |code, row| match &row.result {
+            Ok(result) => code | result.exit_code(),
+            Err(_) => code | EXIT_FAILURE,
+        })
+    }
+
+    /// The first line of an error, to keep each row of the table on one line.
+    fn brief(err: &impl ToString) -> String {
+        err.to_string().lines().next().unwrap_or_default().to_string()
+    }
+
+    fn status(result: &DayResult) -> String {
+        let issues: Vec<String> = [(1, &result.part_one), (2, &result.part_two)]
+            .into_iter()
+            .filter_map(|(part, result): (u8, &PartResult)| match (&result.answer, &result.verdict) {
+                (RunResult::Fail(err), _) => Some(format!("Part {part}: {}", Self::brief(err))),
+                (RunResult::Timeout(_) | RunResult::Panicked(_), _) => {
+                    Some(format!("Part {part}: {}", Self::brief(&result.answer)))
+                },
+                (_, Verdict::Incorrect { expected }) => Some(format!("Part {part}: expected {expected}")),
+                _ => None,
+            })
+            .collect();
+        if issues.is_empty() {
+            "OK".to_string()
+        } else {
+            issues.join("; ")
+        }
+    }
+
+    fn columns(row: &SummaryRow) -> [String; 7] {
+        let answer = |result: &RunResult| match result {
+            RunResult::Success(answer) => answer.to_owned(),
+            _ => "-".to_string(),
+        };
+        match &row.result {
+            Ok(result) => [
+                format!("{:02}", row.day),
+                answer(&result.part_one.answer),
+                answer(&result.part_two.answer),
+                result.parse_time.human(Truncate::Nano).to_string(),
+                result.part_one.time.human(Truncate::Nano).to_string(),
+                result.part_two.time.human(Truncate::Nano).to_string(),
+                Self::status(result),
+            ],
+            Err(err) => [
+                format!("{:02}", row.day),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                "-".to_string(),
+                Self::brief(err),
+            ],
+        }
+    }
+}
+impl Display for Summary {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        let rows: Vec<[String; 7]> = self.rows.iter().map(Self::columns).collect();
+        let total: Duration =
+            self.rows.iter().filter_map(|row| row.result.as_ref().ok()).map(DayResult::total_time).sum();
+        writeln!(f, "{}", table(&Self::HEADINGS, &rows))?;
+        write!(f, "Total ({} days): {}", self.rows.len(), total.human(Truncate::Nano))?;
+
+        // The table only has room for a brief summary of each error.
+        let errors: Vec<String> = self
+            .rows
+            .iter()
+            .flat_map(|row| match &row.result {
+                Ok(result) => [(1, &result.part_one), (2, &result.part_two)]
+                    .into_iter()
+                    .filter(|(_, part)| {
+                        matches!(part.answer, RunResult::Fail(_) | RunResult::Timeout(_) | RunResult::Panicked(_))
+                    })
+                    .map(|(part, result)| format!("Day {:02}, Part {part}: {}", row.day, result.answer))
+                    .collect(),
+                Err(err) => vec![format!("Day {:02}: 💥 {} error: {}", row.day, err.kind(), Chain(err))],
+            })
+            .collect();
+        if !errors.is_empty() {
+            write!(f, "\n\nErrors\n======\n{}", errors.join("\n"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Render rows of columns as a Markdown-style table, padding each column to
+/// its widest value.
+pub(crate) fn table<const N: usize>(headings: &[&str; N], rows: &[[String; N]]) -> String {
+    let mut widths = headings.map(|heading| heading.chars().count());
+    rows.iter().for_each(|row| {
+        row.iter().enumerate().for_each(|(i, column)| widths[i] = widths[i].max(column.chars().count()));
+    });
+    let line = |columns: &[&str]| -> String {
+        let cells: Vec<String> =
+            columns.iter().enumerate().map(|(i, column)| format!("{column:<width$}", width = widths[i])).collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let rule: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+    let mut lines = vec![line(headings), format!("|-{}-|", rule.join("-|-"))];
+    lines.extend(rows.iter().map(|row| line(&row.each_ref().map(String::as_str))));
+    lines.join("\n")
+}
+
+/// How each selected day should be run.
+struct Options {
+    record: bool,
+    format: Format,
+    parts: Parts,
+    /// Path to read the input from instead of the input cache (`-` for stdin).
+    input: Option<String>,
+    timeout: Option<Duration>,
+    /// Warn about any phase more than this many percent slower than usual,
+    /// unless no timing history should be kept.
+    slower_than: Option<f64>,
+    /// How many days to run at once.
+    jobs: usize,
+}
+
 pub struct SingleYear {
     year: u16,
+    registry: Registry,
     cli: Command,
 }
 impl SingleYear {
@@ -52,42 +292,738 @@ impl SingleYear {
             .about(format!("Solution Runner for AOC {}", year))
             .arg(
                 Arg::new("day")
-                    .help("Which day's solution to run?")
+                    .help("Which day's solution to run? Either a day number, a range (\"1..=4\") or \"all\"")
                     .required(true)
                     .index(1)
-                    .value_parser(clap::value_parser!(u8)),
+                    .value_parser(Selection::parse),
             )
+            .args(Self::options())
     }
 
-    pub fn new(year: u16) -> Self {
-        Self { year, cli: Self::build_cli(year) }
+    /// Options for how the selected days get run, shared with [`MultiYear`].
+    fn options() -> [Arg; 14] {
+        [
+            Arg::new("record")
+                .help("Save any answers not already known as the accepted answers for future runs")
+                .long("record")
+                .action(ArgAction::SetTrue),
+            Arg::new("format")
+                .help(format!("Output format, one of: {}", Format::NAMES.join(", ")))
+                .long("format")
+                .default_value("text")
+                .value_parser(Format::parse),
+            Arg::new("bench")
+                .help("Benchmark each phase over this many iterations instead of running once")
+                .long("bench")
+                .value_name("N")
+                .conflicts_with_all([
+                    "format",
+                    "record",
+                    "part",
+                    "parse-only",
+                    "timeout",
+                    "slower-than",
+                    "no-history",
+                    "jobs",
+                ])
+                .value_parser(clap::value_parser!(u32).range(1..)),
+            Arg::new("part")
+                .help("Only run this part of the solution (the input still gets parsed)")
+                .long("part")
+                .value_name("1|2")
+                .conflicts_with("parse-only")
+                .value_parser(clap::value_parser!(u8).range(1..=2)),
+            Arg::new("parse-only")
+                .help("Only parse the input, without running either part")
+                .long("parse-only")
+                .action(ArgAction::SetTrue),
+            Arg::new("input")
+                .help("Read the input from this file instead of the input cache, or \"-\" to read from stdin")
+                .long("input")
+                .short('i')
+                .value_name("PATH"),
+            Arg::new("timeout")
+                .help("Give up on a part after this many seconds, or 0 to wait forever")
+                .long("timeout")
+                .value_name("SECONDS")
+                .default_value(DEFAULT_TIMEOUT)
+                .value_parser(clap::value_parser!(u64)),
+            Arg::new("visualize")
+                .help("Play the day's visualization in the terminal instead of solving it")
+                .long("visualize")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all([
+                    "bench",
+                    "format",
+                    "record",
+                    "part",
+                    "parse-only",
+                    "timeout",
+                    "slower-than",
+                    "no-history",
+                    "jobs",
+                ]),
+            Arg::new("dump-graph")
+                .help(format!(
+                    "Print the day's input drawn as a graph instead of solving it, in one of: {}",
+                    GraphFormat::NAMES.join(", ")
+                ))
+                .long("dump-graph")
+                .value_name("FORMAT")
+                .value_parser(GraphFormat::parse)
+                .conflicts_with_all([
+                    "bench",
+                    "format",
+                    "record",
+                    "part",
+                    "parse-only",
+                    "timeout",
+                    "visualize",
+                    "slower-than",
+                    "no-history",
+                    "jobs",
+                ]),
+            Arg::new("fps")
+                .help("Frames per second to play the visualization at")
+                .long("fps")
+                .requires("visualize")
+                .default_value("10")
+                .value_parser(|value: &str| match value.parse::<f64>() {
+                    Ok(fps) if fps > 0.0 && fps.is_finite() => Ok(fps),
+                    _ => Err(format!("`{value}` is not a positive number")),
+                }),
+            Arg::new("cast")
+                .help("Export the visualization to an asciinema recording instead of playing it")
+                .long("cast")
+                .value_name("PATH")
+                .requires("visualize"),
+            Arg::new("slower-than")
+                .help("Warn when a phase is more than this many percent slower than the median of its recent runs")
+                .long("slower-than")
+                .value_name("PERCENT")
+                .default_value("25")
+                .value_parser(|value: &str| match value.parse::<f64>() {
+                    Ok(percent) if percent >= 0.0 && percent.is_finite() => Ok(percent),
+                    _ => Err(format!("`{value}` is not a percentage")),
+                }),
+            Arg::new("no-history")
+                .help("Don't add the timings of this run to the timing history")
+                .long("no-history")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("slower-than"),
+            Arg::new("jobs")
+                .help(
+                    "Run this many days at once, or 0 for one per CPU. Days that run alongside others are timed less \
+                     accurately, so their timings aren't added to the timing history",
+                )
+                .long("jobs")
+                .short('j')
+                .value_name("N")
+                .default_value("1")
+                .value_parser(clap::value_parser!(usize)),
+        ]
     }
 
-    pub fn run<P>(&mut self, proxy: P) -> i32
-    where
-        // Specify AdventOfCode directly, because `impl Runner` is too difficult.
-        P: Fn(u8, Input) -> Response,
-    {
+    pub fn new(year: u16, registry: Registry) -> Self {
+        Self { year, registry, cli: Self::build_cli(year) }
+    }
+
+    pub fn run(&mut self) -> i32 {
         let matches = self.cli.clone().get_matches();
-        let day: u8 = *matches.get_one::<u8>("day").expect("Day number is required to be a positive integer");
+        self.run_matches(&matches)
+    }
+
+    fn run_matches(&mut self, matches: &ArgMatches) -> i32 {
+        let now = Instant::now();
+        let selection = matches.get_one::<Selection>("day").expect("Day selection is required").clone();
+        let options = Options {
+            record: matches.get_flag("record"),
+            format: *matches.get_one::<Format>("format").expect("Output format has a default value"),
+            parts: match (matches.get_one::<u8>("part"), matches.get_flag("parse-only")) {
+                (Some(1), _) => Parts::ONE,
+                (Some(_), _) => Parts::TWO,
+                (None, true) => Parts::PARSE_ONLY,
+                (None, false) => Parts::BOTH,
+            },
+            input: matches.get_one::<String>("input").cloned(),
+            timeout: matches
+                .get_one::<u64>("timeout")
+                .filter(|seconds| **seconds > 0)
+                .map(|seconds| Duration::from_secs(*seconds)),
+            slower_than: match matches.get_flag("no-history") {
+                true => None,
+                false => matches.get_one::<f64>("slower-than").copied(),
+            },
+            jobs: match *matches.get_one::<usize>("jobs").expect("Number of jobs has a default value") {
+                0 => thread::available_parallelism().map(usize::from).unwrap_or(1),
+                jobs => jobs,
+            },
+        };
+        if options.input.is_some() && !matches!(selection, Selection::Day(_)) {
+            self.cli.error(ErrorKind::ArgumentConflict, "--input can only be used when running a single day").exit();
+        }
+        let days = match selection.clone() {
+            Selection::Day(day) => vec![day],
+            Selection::Range(range) => range.collect(),
+            Selection::All => self.registry.days(),
+        };
+        if let Some(iterations) = matches.get_one::<u32>("bench") {
+            return self.bench(days, *iterations as usize, &options);
+        }
+        if matches.get_flag("visualize") {
+            let Selection::Day(day) = selection else {
+                self.cli.error(ErrorKind::ArgumentConflict, "--visualize can only be used for a single day").exit();
+            };
+            let fps = *matches.get_one::<f64>("fps").expect("Frame rate has a default value");
+            return self.visualize(day, &options, fps, matches.get_one::<String>("cast"));
+        }
+        if let Some(format) = matches.get_one::<GraphFormat>("dump-graph") {
+            let Selection::Day(day) = selection else {
+                self.cli.error(ErrorKind::ArgumentConflict, "--dump-graph can only be used for a single day").exit();
+            };
+            return self.dump_graph(day, &options, *format);
+        }
+        let answers = Answers::load_or_unchecked(self.year);
+        let code = match selection {
+            Selection::Day(day) if options.format == Format::Text => self.run_single(day, &options, &answers),
+            _ => self.run_many(days, &options, &answers),
+        };
+        // Anything other than text is meant to be piped into other tools, so
+        // don't pollute the output.
+        if options.format == Format::Text {
+            println!("Total: {}", now.elapsed().human(Truncate::Nano));
+        }
+        code
+    }
+
+    /// Save the successful answers from each result that aren't already known.
+    fn record<'a>(&self, results: impl IntoIterator<Item = (u8, &'a DayResult)>) -> Result<(), AocError> {
+        let mut answers = Answers::load(self.year)?;
+        let mut changed = false;
+        for (day, result) in results {
+            changed |= answers.record(day, result);
+        }
+        if changed {
+            answers.save(self.year)?;
+            eprintln!("Recorded new answers in {}", Answers::path(self.year).display());
+        }
+        Ok(())
+    }
+
+    /// Add each result to the timing history, warning about any phase that
+    /// has become slower than usual.
+    fn track<'a>(
+        &self,
+        results: impl IntoIterator<Item = (u8, &'a DayResult)>,
+        slower_than: f64,
+    ) -> Result<(), AocError> {
+        let mut history = History::load(self.year)?;
+        let build = Build::current();
+        for (day, result) in results {
+            let Some(record) = Record::new(day, result, &build) else {
+                continue;
+            };
+            history.regressions(&record, slower_than).iter().for_each(|regression| eprintln!("{regression}"));
+            history.append(record)?;
+        }
+        Ok(())
+    }
+
+    /// Submit the answer to a part from the last run against the puzzle
+    /// input, unless the answers submitted before show that it can only be
+    /// wrong. A correct answer is also recorded as the accepted answer.
+    fn submit(&self, day: u8, part: u8) -> i32 {
+        let answer = match self.last_answer(day, part) {
+            Ok(answer) => answer,
+            Err(code) => return code,
+        };
+        println!("Submitting {answer} for {} Day {day}, Part {part}…", self.year);
+        match self.send(day, part, &answer) {
+            Ok(feedback) => {
+                println!("{}: {}", feedback.outcome, feedback.message);
+                match feedback.outcome {
+                    Outcome::Correct => 0,
+                    _ => EXIT_FAILURE,
+                }
+            },
+            Err(err) => {
+                eprintln!("{}", Chain(&err));
+                EXIT_FAILURE
+            },
+        }
+    }
+
+    /// The answer that the last run against the puzzle input showed for a
+    /// part, as kept in the timing history. Without one, the part gets run
+    /// (with the default timeout) to find it.
+    fn last_answer(&self, day: u8, part: u8) -> Result<String, i32> {
+        let phase = if part == 1 { Phase::PartOne } else { Phase::PartTwo };
+        let history = History::load(self.year).unwrap_or_else(|err| {
+            eprintln!("{}", Chain(&err));
+            History::default()
+        });
+        if let Some(run) = history.last_answered(day, phase) {
+            let commit = run.commit.as_deref().unwrap_or("an unknown commit");
+            println!("Using the answer from the run at {} (built from {commit})", run.date());
+            return Ok(run.answer(phase).unwrap_or_default().to_string());
+        }
+        let options = Options {
+            record: false,
+            format: Format::Text,
+            parts: if part == 1 { Parts::ONE } else { Parts::TWO },
+            input: None,
+            timeout: DEFAULT_TIMEOUT.parse().ok().map(Duration::from_secs),
+            slower_than: None,
+            jobs: 1,
+        };
+        let result = match self.solve(day, &options, &Answers::load_or_unchecked(self.year)) {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("💥 {} error: {}", err.kind(), Chain(&err));
+                return Err(EXIT_FAILURE);
+            },
+        };
+        println!("{result}");
+        match if part == 1 { result.part_one.answer } else { result.part_two.answer } {
+            RunResult::Success(answer) => Ok(answer),
+            _ => {
+                eprintln!("Part {part} didn't produce an answer to submit");
+                Err(EXIT_FAILURE)
+            },
+        }
+    }
+
+    fn send(&self, day: u8, part: u8, answer: &str) -> Result<Feedback, AocError> {
+        let mut submissions = Submissions::load(self.year)?;
+        submissions.check(day, part, answer)?;
+        let feedback = Submitter::new(DownloadConfig::load()?).submit(self.year, day, part, answer)?;
+        submissions.append(self.year, Submission::new(day, part, answer, &feedback))?;
+        // The answer has been accepted either way, so failing to remember it
+        // locally shouldn't hide that.
+        if feedback.outcome == Outcome::Correct {
+            if let Err(err) = self.accept(day, part, answer) {
+                eprintln!("{}", Chain(&err));
+            }
+        }
+        Ok(feedback)
+    }
+
+    /// Save an answer that has been accepted, unless one is already known.
+    fn accept(&self, day: u8, part: u8, answer: &str) -> Result<(), AocError> {
+        let mut answers = Answers::load(self.year)?;
+        if answers.accept(day, part, answer) {
+            answers.save(self.year)?;
+            eprintln!("Recorded the accepted answer in {}", Answers::path(self.year).display());
+        }
+        Ok(())
+    }
+
+    /// Look up the solution first, so that an unregistered day isn't
+    /// reported as a missing input.
+    fn solve(&self, day: u8, options: &Options, answers: &Answers) -> Response {
+        Self::isolate(|| {
+            let solution = self.registry.get(day)?;
+            AdventOfCode::run_parts(solution, self.input(day, options)?, options.parts, options.timeout, answers)
+        })
+    }
+
+    /// Make a day that panics outside of either part (while parsing, say)
+    /// only fail that day.
+    ///
+    /// Panics can only be caught when unwinding: with `panic = "abort"` (as
+    /// in the release profile) a panicking day still aborts the whole run,
+    /// so build with the `runner` profile instead.
+    fn isolate(solve: impl FnOnce() -> Response) -> Response {
+        panic::catch_unwind(AssertUnwindSafe(solve))
+            .unwrap_or_else(|payload| Err(AocError::Panicked(AdventOfCode::panic_message(payload))))
+    }
+
+    /// Solve each day, spreading them across as many threads as there are
+    /// jobs. The rows are always in the same order as the days.
+    fn solve_all(&self, days: &[u8], options: &Options, answers: &Answers) -> Vec<SummaryRow> {
+        if options.jobs <= 1 {
+            return days.iter().map(|&day| SummaryRow { day, result: self.solve(day, options, answers) }).collect();
+        }
+        let next = AtomicUsize::new(0);
+        let mut rows: Vec<(usize, SummaryRow)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..options.jobs.min(days.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut rows = Vec::new();
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(&day) = days.get(index) else {
+                                return rows;
+                            };
+                            rows.push((index, SummaryRow { day, result: self.solve(day, options, answers) }));
+                        }
+                    })
+                })
+                .collect();
+            workers.into_iter().flat_map(|worker| worker.join().expect("Days never panic outside of solve")).collect()
+        });
+        rows.sort_by_key(|(index, _)| *index);
+        rows.into_iter().map(|(_, row)| row).collect()
+    }
+
+    fn input(&self, day: u8, options: &Options) -> Result<Input, AocError> {
+        match &options.input {
+            Some(path) => AdventOfCode::get_custom_input(self.year, day, path),
+            None => AdventOfCode::get_input(self.year, day),
+        }
+    }
+
+    fn bench(&self, days: Vec<u8>, iterations: usize, options: &Options) -> i32 {
+        let mut code = 0;
+        for day in days {
+            println!("AOC {:04} (Day {:02}) Benchmark", self.year, day);
+            println!("===========================");
+            let result = self.registry.get(day).and_then(|solution| {
+                let input = self.input(day, options)?;
+                BenchResult::measure(&*solution, input.contents(), iterations)
+            });
+            match result {
+                Ok(result) => {
+                    println!("{result}\n");
+                    if result.is_failure() {
+                        code = EXIT_FAILURE;
+                    }
+                },
+                Err(err) => {
+                    eprintln!("{}\n", Chain(&err));
+                    code = EXIT_FAILURE;
+                },
+            }
+        }
+        code
+    }
+
+    /// Play a day's visualization, or export it to an asciinema recording.
+    fn visualize(&self, day: u8, options: &Options, fps: f64, cast: Option<&String>) -> i32 {
+        let Some(visualization) = self.registry.visualization(day) else {
+            let days = self.registry.visualized_days().iter().map(u8::to_string).collect::<Vec<_>>();
+            eprintln!("Day #{day} has no visualization (available: {})", days.join(", "));
+            return EXIT_FAILURE;
+        };
+        let frames = match self.input(day, options).and_then(|input| visualization.frames(input.contents())) {
+            Ok(frames) => frames,
+            Err(err) => {
+                eprintln!("{}", Chain(&err));
+                return EXIT_FAILURE;
+            },
+        };
+        let played = match cast {
+            Some(path) => File::create(path)
+                .and_then(|file| {
+                    let title = format!("Advent of Code {} (Day {day:02})", self.year);
+                    visualize::write_cast(frames, fps, &title, visualize::terminal_size(), BufWriter::new(file))
+                })
+                .map(|count| println!("Recorded {count} frames to {path}")),
+            None => Player::new(fps).play(frames),
+        };
+        match played {
+            Ok(()) => 0,
+            Err(err) => {
+                eprintln!("{}", Chain(&err));
+                EXIT_FAILURE
+            },
+        }
+    }
+
+    /// Print a day's input drawn as a graph.
+    fn dump_graph(&self, day: u8, options: &Options, format: GraphFormat) -> i32 {
+        let Some(diagram) = self.registry.diagram(day) else {
+            let days = self.registry.diagrammed_days().iter().map(u8::to_string).collect::<Vec<_>>();
+            eprintln!("Day #{day} has no graph to dump (available: {})", days.join(", "));
+            return EXIT_FAILURE;
+        };
+        match self.input(day, options).and_then(|input| diagram.graph(input.contents(), format)) {
+            Ok(graph) => {
+                print!("{graph}");
+                0
+            },
+            Err(err) => {
+                eprintln!("{}", Chain(&err));
+                EXIT_FAILURE
+            },
+        }
+    }
+
+    fn run_single(&self, day: u8, options: &Options, answers: &Answers) -> i32 {
         println!("AOC {:04} (Day {:02})", self.year, day);
         println!("=================");
 
-        let input = AdventOfCode::get_input(self.year, day).unwrap_or_else(|e| {
+        let solution = match self.registry.get(day) {
+            Ok(solution) => solution,
+            Err(err) => {
+                eprintln!("{err}");
+                return EXIT_FAILURE;
+            },
+        };
+        let input = self.input(day, options).unwrap_or_else(|e| {
             eprintln!("Unable to fetch the input for {} Day {day}:", self.year);
-            eprintln!("{e}");
-            ::std::process::exit(2);
+            eprintln!("{}", Chain(&e));
+            ::std::process::exit(EXIT_NO_INPUT);
         });
 
-        match proxy(day, input) {
+        match Self::isolate(|| AdventOfCode::run_parts(solution, input, options.parts, options.timeout, answers)) {
             Ok(result) => {
                 println!("{result}");
+                // The history is only there to compare timings, so it failing
+                // doesn't make the run a failure.
+                if let Some(slower_than) = options.slower_than {
+                    if let Err(err) = self.track([(day, &result)], slower_than) {
+                        eprintln!("{}", Chain(&err));
+                    }
+                }
+                if options.record {
+                    if let Err(err) = self.record([(day, &result)]) {
+                        eprintln!("{}", Chain(&err));
+                        return EXIT_FAILURE;
+                    }
+                }
+                result.exit_code()
+            },
+            Err(err) => {
+                eprintln!("💥 {} error: {}", err.kind(), Chain(&err));
+                EXIT_FAILURE
+            },
+        }
+    }
+
+    fn run_many(&self, days: Vec<u8>, options: &Options, answers: &Answers) -> i32 {
+        if options.format == Format::Text {
+            match options.jobs {
+                1 => println!("AOC {:04} ({} days)", self.year, days.len()),
+                jobs => println!("AOC {:04} ({} days, {jobs} at once)", self.year, days.len()),
+            }
+            println!("=================");
+        }
+
+        let summary = Summary { rows: self.solve_all(&days, options, answers) };
+        let reports =
+            || summary.rows.iter().map(|row| DayReport::new(self.year, row.day, &row.result)).collect::<Vec<_>>();
+        match options.format {
+            Format::Text => println!("{summary}"),
+            Format::Json => println!("{}", report::to_json(&reports())),
+            Format::Csv => println!("{}", report::to_csv(&reports())),
+        }
+        let results = || summary.rows.iter().filter_map(|row| row.result.as_ref().ok().map(|result| (row.day, result)));
+        // Days that ran alongside each other competed for the same CPUs.
+        if let (Some(slower_than), 1) = (options.slower_than, options.jobs) {
+            if let Err(err) = self.track(results(), slower_than) {
+                eprintln!("{}", Chain(&err));
+            }
+        }
+        if options.record {
+            if let Err(err) = self.record(results()) {
+                eprintln!("{}", Chain(&err));
+                return EXIT_FAILURE;
+            }
+        }
+        summary.exit_code()
+    }
+}
+
+/// Front-end for every year at once, run as `aoc <year> <day> [part]`, so
+/// that each year shares the same timing and reporting.
+pub struct MultiYear {
+    years: BTreeMap<u16, Registry>,
+    cli: Command,
+}
+impl MultiYear {
+    fn build_cli(years: Vec<u16>) -> Command {
+        let known = years.iter().map(u16::to_string).collect::<Vec<_>>().join(", ");
+        let about = format!("Solution Runner for AOC {known}");
+        let year = Arg::new("year").help("Which year's solutions to run?").required(true).index(1).value_parser(
+            move |value: &str| match value.trim().parse::<u16>() {
+                Ok(year) if years.contains(&year) => Ok(year),
+                _ => Err(format!("`{value}` is not one of: {known}")),
+            },
+        );
+        Command::new("Advent of Code")
+            .version("1.0")
+            .author("Zan Baldwin <hello@zanbaldwin.com>")
+            .about(about)
+            .arg(year.clone())
+            .arg(
+                Arg::new("day")
+                    .help("Which day's solution to run? Either a day number, a range (\"1..=4\") or \"all\"")
+                    .required(true)
+                    .index(2)
+                    .value_parser(Selection::parse),
+            )
+            .args(SingleYear::options())
+            // Selecting a part is common enough to not need a flag.
+            .mut_arg("part", |arg| arg.long(None).index(3))
+            .subcommand_negates_reqs(true)
+            .args_conflicts_with_subcommands(true)
+            .subcommand(
+                Command::new("new")
+                    .about("Generate a new day from the template for its year, and register it")
+                    .arg(Arg::new("year").required(true).value_parser(clap::value_parser!(u16)))
+                    .arg(Arg::new("day").required(true).value_parser(clap::value_parser!(u8).range(0..=25))),
+            )
+            .subcommand(
+                Command::new("history")
+                    .about("Show how the timings of a day have changed over its recent runs")
+                    .arg(Arg::new("year").required(true).value_parser(clap::value_parser!(u16)))
+                    .arg(Arg::new("day").required(true).value_parser(clap::value_parser!(u8).range(0..=25)))
+                    .arg(
+                        Arg::new("last")
+                            .help("How many of the most recent runs to show")
+                            .long("last")
+                            .value_name("N")
+                            .default_value("20")
+                            .value_parser(clap::value_parser!(usize)),
+                    ),
+            )
+            .subcommand(
+                Command::new("submit")
+                    .about("Run a part against the puzzle input, and submit its answer")
+                    .arg(year)
+                    .arg(Arg::new("day").required(true).index(2).value_parser(clap::value_parser!(u8).range(1..=25)))
+                    .arg(Arg::new("part").required(true).index(3).value_parser(clap::value_parser!(u8).range(1..=2))),
+            )
+    }
+
+    pub fn new(years: impl IntoIterator<Item = (u16, Registry)>) -> Self {
+        let years: BTreeMap<u16, Registry> = years.into_iter().collect();
+        let cli = Self::build_cli(years.keys().copied().collect());
+        Self { years, cli }
+    }
+
+    pub fn run(&mut self) -> i32 {
+        let matches = self.cli.clone().get_matches();
+        if let Some((command, matches)) = matches.subcommand() {
+            let year = *matches.get_one::<u16>("year").expect("Year is required");
+            let day = *matches.get_one::<u8>("day").expect("Day is required");
+            return match command {
+                "history" => Self::history(year, day, *matches.get_one::<usize>("last").expect("Has a default value")),
+                "submit" => {
+                    let part = *matches.get_one::<u8>("part").expect("Part is required");
+                    let registry = self.years.remove(&year).expect("Only known years can be selected");
+                    SingleYear { year, registry, cli: self.cli.clone() }.submit(day, part)
+                },
+                _ => Self::scaffold(year, day),
+            };
+        }
+        let year = *matches.get_one::<u16>("year").expect("Year is required");
+        let registry = self.years.remove(&year).expect("Only known years can be selected");
+        SingleYear { year, registry, cli: self.cli.clone() }.run_matches(&matches)
+    }
+
+    /// Show the timing history of a day, followed by the median of each
+    /// phase for every build profile it has been run with.
+    fn history(year: u16, day: u8, last: usize) -> i32 {
+        let history = match History::load(year) {
+            Ok(history) => history,
+            Err(err) => {
+                eprintln!("{}", Chain(&err));
+                return EXIT_FAILURE;
+            },
+        };
+        if history.runs(day).next().is_none() {
+            eprintln!("Day #{day} has no timing history yet in {}", History::path(year).display());
+            return EXIT_FAILURE;
+        }
+        println!("AOC {year:04} (Day {day:02}) History");
+        println!("=========================");
+        println!("{}\n", history.trend(day, last));
+        let mut profiles: Vec<&str> = history.runs(day).map(|record| record.profile.as_str()).collect();
+        profiles.sort();
+        profiles.dedup();
+        for profile in profiles {
+            let medians: Vec<String> = Phase::ALL
+                .into_iter()
+                .filter_map(|phase| {
+                    let (median, runs) = history.median(day, phase, profile)?;
+                    let phase = phase.to_string().to_lowercase();
+                    let runs = match runs {
+                        1 => "1 run".to_string(),
+                        runs => format!("{runs} runs"),
+                    };
+                    Some(format!("{phase} {} (of {runs})", median.human(Truncate::Nano)))
+                })
+                .collect();
+            println!("Median of recent {profile} runs: {}", medians.join(", "));
+        }
+        0
+    }
+
+    /// Generate a new day, relative to the current directory (the root of the
+    /// repository, like the inputs are).
+    fn scaffold(year: u16, day: u8) -> i32 {
+        match Scaffold::new(".", year, day).and_then(|scaffold| scaffold.generate()) {
+            Ok(changes) => {
+                changes.iter().for_each(|change| println!("{change}"));
                 0
             },
             Err(err) => {
-                eprintln!("{err}");
-                1
+                eprintln!("{}", Chain(&err));
+                EXIT_FAILURE
             },
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(Ok(Selection::Day(4)), Selection::parse("4"));
+        assert_eq!(Ok(Selection::All), Selection::parse("all"));
+        assert_eq!(Ok(Selection::Range(1..=4)), Selection::parse("1..=4"));
+        assert_eq!(Ok(Selection::Range(1..=3)), Selection::parse("1..4"));
+        assert!(Selection::parse("1..1").is_err());
+        assert!(Selection::parse("4..=1").is_err());
+        assert!(Selection::parse("first").is_err());
+    }
+
+    #[test]
+    fn test_cli() {
+        SingleYear::build_cli(2024).debug_assert();
+        MultiYear::build_cli(vec![2023, 2024]).debug_assert();
+    }
+
+    #[test]
+    fn test_solve_all_in_order() {
+        struct Sleepy(u64);
+        impl crate::Solution for Sleepy {
+            type Parsed = ();
+            fn parse(&self, _: &str) -> Result<(), crate::ParseError> {
+                assert!(self.0 > 0, "Nothing to sleep on");
+                thread::sleep(Duration::from_millis(self.0));
+                Ok(())
+            }
+            fn part1(&self, _: &()) -> Result<impl Display, crate::RunnerError> {
+                Ok(self.0)
+            }
+            fn part2(&self, _: &()) -> Result<impl Display, crate::RunnerError> {
+                Err::<u64, _>(crate::RunnerError::Unimplemented)
+            }
+        }
+        let path = std::env::temp_dir().join(format!("aoc-solve-all-{}.txt", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+        let mut registry = Registry::new();
+        [(1, 30), (2, 0), (3, 1), (5, 10)].into_iter().for_each(|(day, millis)| registry.register(day, Sleepy(millis)));
+        let options = Options {
+            record: false,
+            format: Format::Json,
+            parts: Parts::BOTH,
+            input: Some(path.to_string_lossy().to_string()),
+            timeout: None,
+            slower_than: None,
+            jobs: 3,
+        };
+        let rows = SingleYear::new(2024, registry).solve_all(&[1, 2, 3, 4, 5], &options, &Answers::default());
+        assert_eq!(vec![1, 2, 3, 4, 5], rows.iter().map(|row| row.day).collect::<Vec<_>>());
+        let answer = |row: &SummaryRow| match &row.result {
+            Ok(result) => result.part_one.answer.to_string(),
+            Err(err) => err.kind().to_string(),
+        };
+        assert_eq!(vec!["30", "Panic", "1", "OutOfScope", "10"], rows.iter().map(answer).collect::<Vec<_>>());
+    }
+}
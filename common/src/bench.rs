@@ -0,0 +1,188 @@
+use crate::app::table;
+use crate::registry::DynSolution;
+use crate::{AocError, Chain, RunnerError};
+use humanize_duration::{prelude::DurationExt, Truncate};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Summary statistics over repeated timings of a single phase.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub p95: Duration,
+    pub std_dev: Duration,
+}
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "cannot calculate statistics without any samples");
+        samples.sort_unstable();
+        let count = samples.len();
+        let nanos: Vec<f64> = samples.iter().map(|sample| sample.as_nanos() as f64).collect();
+        let mean = nanos.iter().sum::<f64>() / count as f64;
+        let variance = nanos.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / count as f64;
+        let median = if count.is_multiple_of(2) {
+            (samples[count / 2 - 1] + samples[count / 2]) / 2
+        } else {
+            samples[count / 2]
+        };
+        // Nearest-rank percentile.
+        let p95 = samples[((count as f64 * 0.95).ceil() as usize).clamp(1, count) - 1];
+        Self {
+            min: samples[0],
+            median,
+            mean: Duration::from_nanos(mean.round() as u64),
+            p95,
+            std_dev: Duration::from_nanos(variance.sqrt().round() as u64),
+        }
+    }
+}
+
+pub struct BenchResult {
+    iterations: usize,
+    parse: Stats,
+    /// Parts that fail aren't benchmarked, as that would only measure how
+    /// quickly they give up.
+    part_one: Result<Stats, RunnerError>,
+    part_two: Result<Stats, RunnerError>,
+}
+impl BenchResult {
+    /// Number of warm-up runs performed before any timings are recorded.
+    fn warm_up(iterations: usize) -> usize {
+        (iterations / 10).clamp(1, 100)
+    }
+
+    /// Run every phase of a solution repeatedly. Inputs and outputs are passed
+    /// through [`black_box`] so that the optimizer can't skip any of the work.
+    pub fn measure(solution: &dyn DynSolution, input: &str, iterations: usize) -> Result<Self, AocError> {
+        let iterations = iterations.max(1);
+        // The first warm-up run finds out which parts can be benchmarked.
+        let parsed = solution.parse(black_box(input))?;
+        let part_one_error = black_box(solution.part1(&parsed)).err();
+        let part_two_error = black_box(solution.part2(&parsed)).err();
+        let (part_one_runs, part_two_runs) = (part_one_error.is_none(), part_two_error.is_none());
+        for _ in 1..Self::warm_up(iterations) {
+            let parsed = solution.parse(black_box(input))?;
+            if part_one_runs {
+                black_box(solution.part1(&parsed).ok());
+            }
+            if part_two_runs {
+                black_box(solution.part2(&parsed).ok());
+            }
+        }
+
+        let mut parse = Vec::with_capacity(iterations);
+        let mut part_one = Vec::with_capacity(iterations);
+        let mut part_two = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let now = Instant::now();
+            let parsed = black_box(solution.parse(black_box(input))?);
+            parse.push(now.elapsed());
+
+            if part_one_runs {
+                let now = Instant::now();
+                black_box(solution.part1(black_box(&parsed)).ok());
+                part_one.push(now.elapsed());
+            }
+
+            if part_two_runs {
+                let now = Instant::now();
+                black_box(solution.part2(black_box(&parsed)).ok());
+                part_two.push(now.elapsed());
+            }
+        }
+
+        Ok(Self {
+            iterations,
+            parse: Stats::from_samples(parse),
+            part_one: part_one_error.map_or_else(|| Ok(Stats::from_samples(part_one)), Err),
+            part_two: part_two_error.map_or_else(|| Ok(Stats::from_samples(part_two)), Err),
+        })
+    }
+
+    /// Whether a part failed in a way other than not being implemented yet.
+    pub fn is_failure(&self) -> bool {
+        [&self.part_one, &self.part_two].into_iter().any(|part| match part {
+            Err(RunnerError::Unimplemented) | Ok(_) => false,
+            Err(_) => true,
+        })
+    }
+}
+impl Display for BenchResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let human = |duration: Duration| duration.human(Truncate::Nano).to_string();
+        let phases =
+            [("Parse", Ok(&self.parse)), ("Part 1", self.part_one.as_ref()), ("Part 2", self.part_two.as_ref())];
+        let rows = phases.each_ref().map(|(phase, stats)| match stats {
+            Ok(stats) => [
+                phase.to_string(),
+                human(stats.min),
+                human(stats.median),
+                human(stats.mean),
+                human(stats.p95),
+                format!("± {}", human(stats.std_dev)),
+            ],
+            Err(_) => [phase.to_string(), "-".into(), "-".into(), "-".into(), "-".into(), "-".into()],
+        });
+        writeln!(f, "{} iterations (after {} warm-up runs)", self.iterations, Self::warm_up(self.iterations))?;
+        write!(f, "{}", table(&["Phase", "Min", "Median", "Mean", "p95", "Std Dev"], &rows))?;
+        for (phase, stats) in phases {
+            if let Err(err) = stats {
+                write!(f, "\n{phase} wasn't benchmarked: 💥 {} error: {}", err.kind(), Chain(err))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ns(nanos: &[u64]) -> Vec<Duration> {
+        nanos.iter().copied().map(Duration::from_nanos).collect()
+    }
+
+    #[test]
+    fn test_stats() {
+        let stats = Stats::from_samples(ns(&[5, 1, 4, 2, 3]));
+        assert_eq!(Duration::from_nanos(1), stats.min);
+        assert_eq!(Duration::from_nanos(3), stats.median);
+        assert_eq!(Duration::from_nanos(3), stats.mean);
+        assert_eq!(Duration::from_nanos(5), stats.p95);
+        // Population standard deviation of 1..=5 is √2.
+        assert_eq!(Duration::from_nanos(1), stats.std_dev);
+    }
+
+    #[test]
+    fn test_failing_parts_are_not_benchmarked() {
+        struct Unfinished;
+        impl crate::Solution for Unfinished {
+            type Parsed = ();
+            fn parse(&self, _: &str) -> Result<(), crate::ParseError> {
+                Ok(())
+            }
+            fn part1(&self, _: &()) -> Result<impl Display, RunnerError> {
+                Ok(1)
+            }
+            fn part2(&self, _: &()) -> Result<impl Display, RunnerError> {
+                Err::<u8, _>(RunnerError::Custom("gave up".to_string()))
+            }
+        }
+        let result = BenchResult::measure(&Unfinished, "", 10).unwrap();
+        assert!(result.part_one.is_ok());
+        assert!(matches!(result.part_two, Err(RunnerError::Custom(_))));
+        assert!(result.is_failure());
+        assert!(result.to_string().ends_with("Part 2 wasn't benchmarked: 💥 Custom error: gave up"));
+    }
+
+    #[test]
+    fn test_stats_percentile() {
+        let samples: Vec<u64> = (1..=100).collect();
+        let stats = Stats::from_samples(ns(&samples));
+        assert_eq!(Duration::from_nanos(95), stats.p95);
+        assert_eq!(Duration::from_nanos(50), stats.median);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::AocError;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+const DEFAULT_USER_AGENT: &str = "github.com/zanbaldwin/aoc by hello@zanbaldwin.com";
+const DEFAULT_THROTTLE: Duration = Duration::from_secs(5);
+/// Puzzles unlock at midnight EST (UTC-5).
+const UNLOCK_HOUR_UTC: u64 = 5;
+
+/// Settings for fetching puzzle inputs and submitting answers, read from
+/// `~/.config/aoc/config.toml` (or the file named by `AOC_CONFIG`) and
+/// overridden by the `AOC_SESSION` and `AOC_BASE_URL` environment variables:
+///
+/// ```toml
+/// session = "53616c7465645f5f..."
+/// base_url = "http://localhost:8080"
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct DownloadConfig {
+    session: Option<String>,
+    base_url: Option<String>,
+    user_agent: Option<String>,
+    throttle_seconds: Option<u64>,
+}
+impl DownloadConfig {
+    fn config_path() -> Option<PathBuf> {
+        env::var_os("AOC_CONFIG").map(PathBuf::from).or_else(|| {
+            env::var_os("XDG_CONFIG_HOME")
+                .map(PathBuf::from)
+                .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+                .map(|config| config.join("aoc").join("config.toml"))
+        })
+    }
+
+    pub fn load() -> Result<Self, AocError> {
+        let mut config = match Self::config_path().filter(|path| path.exists()) {
+            Some(path) => toml::from_str(&fs::read_to_string(&path)?)
+                .map_err(|err| AocError::Download(format!("Invalid config file {}: {err}", path.display())))?,
+            None => Self::default(),
+        };
+        if let Ok(session) = env::var("AOC_SESSION") {
+            config.session = Some(session);
+        }
+        if let Ok(base_url) = env::var("AOC_BASE_URL") {
+            config.base_url = Some(base_url);
+        }
+        Ok(config)
+    }
+
+    /// Downloading is opt-in: only attempt it when a session token has been configured.
+    pub fn is_enabled(&self) -> bool {
+        self.session.as_ref().is_some_and(|session| !session.trim().is_empty())
+    }
+
+    pub(crate) fn base_url(&self) -> &str {
+        self.base_url.as_deref().unwrap_or(DEFAULT_BASE_URL).trim_end_matches('/')
+    }
+
+    pub(crate) fn session(&self) -> Option<&str> {
+        self.session.as_deref().map(str::trim)
+    }
+
+    /// An HTTP agent that identifies itself, and doesn't wait forever.
+    pub(crate) fn agent(&self) -> ureq::Agent {
+        ureq::AgentBuilder::new()
+            .user_agent(self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+            .timeout(Duration::from_secs(30))
+            .build()
+    }
+
+    fn throttle(&self) -> Duration {
+        self.throttle_seconds.map(Duration::from_secs).unwrap_or(DEFAULT_THROTTLE)
+    }
+}
+
+/// Unix timestamp for midnight at the start of the given date (UTC).
+/// Uses Howard Hinnant's `days_from_civil` to avoid pulling in a date library.
+fn unix_midnight(year: u16, month: u8, day: u8) -> u64 {
+    let (month, day) = (month as i64, day as i64);
+    let year = year as i64 - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = era * 146097 + day_of_era - 719468;
+    (days * 86400) as u64
+}
+
+pub(crate) fn unlocks_at(year: u16, day: u8) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(unix_midnight(year, 12, day) + UNLOCK_HOUR_UTC * 3600)
+}
+
+/// Fetches puzzle inputs from the Advent of Code website (or a compatible
+/// server), politely: with an identifying User-Agent, never before a puzzle
+/// has unlocked, and no more often than the configured throttle allows.
+pub struct Downloader {
+    config: DownloadConfig,
+    throttle_file: PathBuf,
+}
+impl Downloader {
+    pub fn new(config: DownloadConfig, cache_directory: impl AsRef<Path>) -> Self {
+        Self {
+            config,
+            throttle_file: cache_directory.as_ref().join(".last-request"),
+        }
+    }
+
+    fn wait_for_throttle(&self) {
+        let last = fs::read_to_string(&self.throttle_file)
+            .ok()
+            .and_then(|contents| contents.trim().parse::<u64>().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+        if let Some(elapsed) = last.and_then(|last| SystemTime::now().duration_since(last).ok()) {
+            if let Some(remaining) = self.config.throttle().checked_sub(elapsed) {
+                thread::sleep(remaining);
+            }
+        }
+    }
+
+    fn mark_request(&self) -> Result<(), AocError> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        if let Some(directory) = self.throttle_file.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(&self.throttle_file, now.as_millis().to_string())?;
+        Ok(())
+    }
+
+    pub fn fetch(&self, year: u16, day: u8) -> Result<String, AocError> {
+        if !(1..=25).contains(&day) {
+            return Err(AocError::Download(format!("Day {day} is not an Advent of Code puzzle")));
+        }
+        if SystemTime::now() < unlocks_at(year, day) {
+            return Err(AocError::Download(format!("{year} Day {day} has not unlocked yet")));
+        }
+        let session = self.config.session().ok_or_else(|| {
+            AocError::Download("No session token configured (set AOC_SESSION or the config file)".to_string())
+        })?;
+
+        self.wait_for_throttle();
+        self.mark_request()?;
+        let url = format!("{}/{}/day/{}/input", self.config.base_url(), year, day);
+        let response = self
+            .config
+            .agent()
+            .get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .call()
+            .map_err(|err| AocError::Download(format!("Request to {url} failed: {err}")))?;
+        response.into_string().map_err(AocError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_unlock_time() {
+        // 2024-12-01T05:00:00Z
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(1733029200), unlocks_at(2024, 1));
+        // 2023-12-25T05:00:00Z
+        assert_eq!(UNIX_EPOCH + Duration::from_secs(1703480400), unlocks_at(2023, 25));
+    }
+
+    #[test]
+    fn test_refuses_locked_puzzles() {
+        let config = DownloadConfig {
+            session: Some("abc".to_string()),
+            ..Default::default()
+        };
+        let downloader = Downloader::new(config, env::temp_dir());
+        assert!(matches!(downloader.fetch(9999, 1), Err(AocError::Download(_))));
+        assert!(matches!(downloader.fetch(2024, 26), Err(AocError::Download(_))));
+    }
+
+    #[test]
+    fn test_fetch_from_stub_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let request: Vec<String> =
+                BufReader::new(&stream).lines().map_while(Result::ok).take_while(|line| !line.is_empty()).collect();
+            let body = "1 2 3\n";
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}", body.len())
+                .unwrap();
+            request
+        });
+
+        let cache = env::temp_dir().join(format!("aoc-download-test-{}", std::process::id()));
+        let config = DownloadConfig {
+            session: Some("secret".to_string()),
+            base_url: Some(format!("http://{address}/")),
+            throttle_seconds: Some(0),
+            ..Default::default()
+        };
+        let input = Downloader::new(config, &cache).fetch(2015, 1).unwrap();
+        let request = server.join().unwrap();
+        fs::remove_dir_all(cache).ok();
+
+        assert_eq!("1 2 3\n", input);
+        assert_eq!("GET /2015/day/1/input HTTP/1.1", request[0]);
+        assert!(request.iter().any(|line| line.eq_ignore_ascii_case("cookie: session=secret")));
+        assert!(request.iter().any(|line| line.to_ascii_lowercase().starts_with("user-agent: github.com/zanbaldwin")));
+    }
+}
@@ -1,8 +1,43 @@
+use nom::error::{Error as NomError, ErrorKind as NomErrorKind};
+use nom::Err as NomErr;
+use serde::Serialize;
 use std::error::Error as RustError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::io::Error as IoError;
 use std::num::ParseIntError;
 use thiserror::Error;
 
+/// Where in the input a parser gave up, along with the offending line.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+    pub snippet: String,
+}
+impl Location {
+    /// Find the position of `remaining` within `input`; `remaining` is
+    /// expected to be a suffix of `input`, as is the case with Nom.
+    pub fn find(input: &str, remaining: &str) -> Self {
+        let offset = input.len().saturating_sub(remaining.len());
+        let offset = (0..=offset).rev().find(|offset| input.is_char_boundary(*offset)).unwrap_or(0);
+        let consumed = &input[..offset];
+        let line_start = consumed.rfind('\n').map(|newline| newline + 1).unwrap_or(0);
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            snippet: input[line_start..].lines().next().unwrap_or_default().to_string(),
+        }
+    }
+}
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let gutter = " ".repeat(self.line.to_string().len());
+        writeln!(f, "line {}, column {}:", self.line, self.column)?;
+        writeln!(f, "{} | {}", self.line, self.snippet)?;
+        write!(f, "{gutter} | {}^", " ".repeat(self.column - 1))
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ParseError {
     #[error("{0}")]
@@ -11,6 +46,42 @@ pub enum ParseError {
     Int(ParseIntError),
     #[error("Nomming went bad: {0}")]
     Nom(String),
+    #[error("{message} at {location}")]
+    Syntax { message: String, location: Location },
+}
+impl ParseError {
+    /// Name of the variant, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Custom(_) => "Custom",
+            Self::Int(_) => "Int",
+            Self::Nom(_) => "Nom",
+            Self::Syntax { .. } => "Syntax",
+        }
+    }
+
+    /// An error at the point in `input` where only `remaining` was left unparsed.
+    pub fn at(input: &str, remaining: &str, message: impl Into<String>) -> Self {
+        Self::Syntax {
+            message: message.into(),
+            location: Location::find(input, remaining),
+        }
+    }
+
+    /// Convert the error returned by a Nom parser, keeping track of where in
+    /// `input` (the same input that was given to the parser) it failed.
+    pub fn nom(input: &str, err: NomErr<NomError<&str>>) -> Self {
+        match err {
+            NomErr::Incomplete(needed) => Self::Nom(format!("Incomplete input: {needed:?}")),
+            NomErr::Error(err) | NomErr::Failure(err) => {
+                let message = match err.code {
+                    NomErrorKind::Eof => "Unexpected input".to_string(),
+                    kind => format!("Expected {}", kind.description()),
+                };
+                Self::at(input, err.input, message)
+            },
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -20,20 +91,86 @@ pub enum RunnerError {
     #[error("Not yet implemented")]
     Unimplemented,
     #[error("Unknown error: {0}")]
-    Other(#[from] Box<dyn RustError>),
+    Other(#[from] Box<dyn RustError + Send + Sync>),
+}
+impl RunnerError {
+    /// Name of the variant, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Custom(_) => "Custom",
+            Self::Unimplemented => "Unimplemented",
+            Self::Other(_) => "Other",
+        }
+    }
 }
 
 #[derive(Debug, Error)]
 pub enum AocError {
     #[error("Could not fetch input: {0}")]
     Io(IoError),
-    #[error("Day #{0} not a registered solution")]
-    OutOfScope(u8),
+    #[error("Day #{0} not a registered solution (available: {days})", days = list_days(.1))]
+    OutOfScope(u8, Vec<u8>),
     #[error("Could not parse input into data model: {0}")]
     Parse(ParseError),
     #[error("Could not run solution against data model: {0}")]
     Run(RunnerError),
+    #[error("Could not read known answers: {0}")]
+    Answers(String),
+    #[error("Could not download input: {0}")]
+    Download(String),
+    #[error("Could not generate day: {0}")]
+    Scaffold(String),
+    #[error("Could not read timing history: {0}")]
+    History(String),
+    #[error("Solution panicked: {0}")]
+    Panicked(String),
+    #[error("Could not submit answer: {0}")]
+    Submit(String),
+}
+impl AocError {
+    /// Name of the variant, for machine-readable output.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "Io",
+            Self::OutOfScope(..) => "OutOfScope",
+            Self::Parse(_) => "Parse",
+            Self::Run(_) => "Run",
+            Self::Answers(_) => "Answers",
+            Self::Download(_) => "Download",
+            Self::Scaffold(_) => "Scaffold",
+            Self::History(_) => "History",
+            Self::Panicked(_) => "Panic",
+            Self::Submit(_) => "Submit",
+        }
+    }
 }
+/// Displays an error followed by each error in its chain of sources, skipping
+/// any source that the error already includes in its own message.
+pub struct Chain<'a>(pub &'a (dyn RustError + 'static));
+impl Display for Chain<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut message = self.0.to_string();
+        write!(f, "{message}")?;
+        let mut source = self.0.source();
+        while let Some(err) = source {
+            let next = err.to_string();
+            if !message.contains(&next) {
+                write!(f, "\n  caused by: {next}")?;
+            }
+            message = next;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+fn list_days(days: &[u8]) -> String {
+    if days.is_empty() {
+        return "none".to_string();
+    }
+    days.iter().map(u8::to_string).collect::<Vec<_>>().join(", ")
+}
+
 impl From<IoError> for AocError {
     fn from(value: IoError) -> Self {
         AocError::Io(value)
@@ -49,3 +186,49 @@ impl From<RunnerError> for AocError {
         AocError::Run(value)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::bytes::complete::tag;
+    use nom::IResult;
+
+    const INPUT: &str = "broadcaster -> a\n%a -> b\n$b -> a";
+
+    #[test]
+    fn test_location() {
+        let location = Location::find(INPUT, &INPUT[25..]);
+        assert_eq!(Location { line: 3, column: 1, snippet: "$b -> a".to_string() }, location);
+        assert_eq!("line 3, column 1:\n3 | $b -> a\n  | ^", location.to_string());
+        assert_eq!(
+            Location {
+                line: 1,
+                column: 13,
+                snippet: "broadcaster -> a".to_string()
+            },
+            Location::find(INPUT, &INPUT[12..])
+        );
+    }
+
+    #[test]
+    fn test_from_nom() {
+        fn parser(input: &str) -> IResult<&str, &str> {
+            tag("%")(input)
+        }
+        let remaining = &INPUT[25..];
+        let err = ParseError::nom(INPUT, parser(remaining).unwrap_err());
+        assert_eq!("Syntax", err.kind());
+        assert!(err.to_string().starts_with("Expected Tag at line 3, column 1"));
+    }
+
+    #[test]
+    fn test_chain() {
+        let err = RunnerError::Other(Box::new(IoError::other("disk on fire")));
+        assert_eq!("Unknown error: disk on fire", Chain(&err).to_string());
+        #[derive(Debug, Error)]
+        #[error("could not read the manual")]
+        struct Outer(#[source] IoError);
+        let err = RunnerError::Other(Box::new(Outer(IoError::other("disk on fire"))));
+        assert_eq!("Unknown error: could not read the manual\n  caused by: disk on fire", Chain(&err).to_string());
+    }
+}
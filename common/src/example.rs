@@ -0,0 +1,142 @@
+use crate::Solution;
+use std::fs;
+use std::path::Path;
+
+/// A worked example from a puzzle description, stored as a fixture under
+/// `examples/{year}/{day}/*.txt`. The expected answers go in a header that
+/// ends at the first `---` line, and either part may be left out:
+///
+/// ```text
+/// # Comments explain where the example came from.
+/// part1: 11
+/// part2: 31
+/// ---
+/// 3   4
+/// 4   3
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Example {
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+    pub input: String,
+}
+impl Example {
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut header = Vec::new();
+        let mut offset = None;
+        let mut position = 0;
+        for line in contents.split_inclusive('\n') {
+            position += line.len();
+            if line.trim_end() == "---" {
+                offset = Some(position);
+                break;
+            }
+            header.push(line.trim());
+        }
+        let offset = offset.ok_or_else(|| "Missing `---` line between the header and the input".to_string())?;
+        let mut example = Self {
+            input: contents[offset..].to_string(),
+            ..Self::default()
+        };
+        for line in header.into_iter().filter(|line| !line.is_empty() && !line.starts_with('#')) {
+            let (key, value) = line.split_once(':').ok_or_else(|| format!("`{line}` is not a `key: value` pair"))?;
+            let answer = match key.trim() {
+                "part1" => &mut example.part1,
+                "part2" => &mut example.part2,
+                key => return Err(format!("Unknown key `{key}`, expected `part1` or `part2`")),
+            };
+            *answer = Some(value.trim().to_string());
+        }
+        Ok(example)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path).map_err(|err| format!("Could not read {}: {err}", path.display()))?;
+        Self::parse(&contents).map_err(|err| format!("Invalid example {}: {err}", path.display()))
+    }
+
+    pub fn expected(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Assert that a solution produces the expected answer for one part of an
+/// example fixture. Examples without an answer for that part pass trivially.
+pub fn check<S: Solution>(solution: &S, path: &Path, part: u8) {
+    let example = Example::load(path).unwrap_or_else(|err| panic!("{err}"));
+    let Some(expected) = example.expected(part) else {
+        return;
+    };
+    let parsed =
+        solution.parse(&example.input).unwrap_or_else(|err| panic!("Could not parse {}: {err}", path.display()));
+    let answer = match part {
+        1 => solution.part1(&parsed).map(|answer| answer.to_string()),
+        _ => solution.part2(&parsed).map(|answer| answer.to_string()),
+    };
+    let answer = answer.unwrap_or_else(|err| panic!("Part {part} of {} failed: {err}", path.display()));
+    assert_eq!(expected, answer, "Part {part} of {}", path.display());
+}
+
+/// Generate a test for each part of every example fixture matching a glob
+/// (relative to the calling crate's manifest), named `example_part_one` and
+/// `example_part_two` with one case per file. The calling crate needs
+/// `rstest` as a dev-dependency. Fixtures are found at compile time, so a new
+/// fixture only gets picked up once the calling file is recompiled.
+///
+/// ```rust,ignore
+/// #[cfg(test)]
+/// mod tests {
+///     use super::*;
+///
+///     aoc_common::example_tests!(Day01 {}, "../examples/2024/01/*.txt");
+/// }
+/// ```
+#[macro_export]
+macro_rules! example_tests {
+    ($solution:expr, $files:tt) => {
+        #[::rstest::rstest]
+        fn example_part_one(#[files($files)] path: ::std::path::PathBuf) {
+            $crate::example::check(&$solution, &path, 1);
+        }
+
+        #[::rstest::rstest]
+        fn example_part_two(#[files($files)] path: ::std::path::PathBuf) {
+            $crate::example::check(&$solution, &path, 2);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_example() {
+        let example = Example::parse("# Example\npart1: 11\n\npart2:  31 \n---\n3   4\n4   3\n").unwrap();
+        assert_eq!(Some("11"), example.expected(1));
+        assert_eq!(Some("31"), example.expected(2));
+        assert_eq!("3   4\n4   3\n", example.input);
+    }
+
+    #[test]
+    fn test_parse_partial_example() {
+        let example = Example::parse("part2: 48\n---\nxmul(2,4)").unwrap();
+        assert_eq!(None, example.expected(1));
+        assert_eq!(Some("48"), example.expected(2));
+        assert_eq!(
+            Example { input: "1 2\r\n".to_string(), ..Example::default() },
+            Example::parse("---\r\n1 2\r\n").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_invalid_examples() {
+        assert!(Example::parse("part1: 11\n3   4").is_err());
+        assert!(Example::parse("part3: 11\n---\n3   4").is_err());
+        assert!(Example::parse("part1 11\n---\n3   4").is_err());
+    }
+}
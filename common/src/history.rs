@@ -0,0 +1,379 @@
+use crate::app::table;
+use crate::{AocError, DayResult, RunResult};
+use humanize_duration::{prelude::DurationExt, Truncate};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How many of the most recent runs a new run gets compared against.
+pub const WINDOW: usize = 10;
+/// How many earlier runs there have to be before a run can be called slower.
+const MINIMUM_RUNS: usize = 3;
+
+/// A timed phase of running a day.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Phase {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+impl Phase {
+    pub const ALL: [Self; 3] = [Self::Parse, Self::PartOne, Self::PartTwo];
+}
+impl Display for Phase {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Parse => write!(f, "Parsing"),
+            Self::PartOne => write!(f, "Part 1"),
+            Self::PartTwo => write!(f, "Part 2"),
+        }
+    }
+}
+
+/// What the solutions were built from, so that timings are only compared
+/// between builds that are comparable. Both are captured when `aoc-common` is
+/// built, by its build script.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Build {
+    /// As described by `git describe --always --dirty`, when built from a
+    /// repository. Whether it's dirty is only as of the last time any staged
+    /// files changed.
+    pub commit: Option<String>,
+    /// The name of the Cargo profile, such as `debug` or `runner`.
+    pub profile: String,
+}
+impl Build {
+    pub fn current() -> Self {
+        Self {
+            commit: option_env!("AOC_BUILD_COMMIT").map(String::from),
+            profile: env!("AOC_BUILD_PROFILE").to_string(),
+        }
+    }
+}
+
+/// The timings of a single run of a day. Parts that were skipped or didn't
+/// produce an answer aren't timed, as their timings say nothing about how
+/// fast the solution is.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Record {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub year: u16,
+    pub day: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub profile: String,
+    pub parse_ns: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1_ns: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2_ns: Option<u64>,
+    /// The answers that were shown, so that they can be submitted later.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part1_answer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub part2_answer: Option<String>,
+}
+impl Record {
+    /// Timings from a run against a custom input can't be compared with
+    /// runs against the puzzle input, so they don't get a record.
+    pub fn new(day: u8, result: &DayResult, build: &Build) -> Option<Self> {
+        if result.input.is_custom() {
+            return None;
+        }
+        let nanos = |time: Duration| time.as_nanos().try_into().unwrap_or(u64::MAX);
+        let part = |part: &crate::PartResult| match part.answer {
+            RunResult::Success(_) => Some(nanos(part.time)),
+            _ => None,
+        };
+        let answer = |part: &crate::PartResult| match &part.answer {
+            RunResult::Success(answer) => Some(answer.clone()),
+            _ => None,
+        };
+        Some(Self {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or_default(),
+            year: result.input.year(),
+            day,
+            commit: build.commit.clone(),
+            profile: build.profile.clone(),
+            parse_ns: nanos(result.parse_time),
+            part1_ns: part(&result.part_one),
+            part2_ns: part(&result.part_two),
+            part1_answer: answer(&result.part_one),
+            part2_answer: answer(&result.part_two),
+        })
+    }
+
+    pub fn time(&self, phase: Phase) -> Option<Duration> {
+        match phase {
+            Phase::Parse => Some(self.parse_ns),
+            Phase::PartOne => self.part1_ns,
+            Phase::PartTwo => self.part2_ns,
+        }
+        .map(Duration::from_nanos)
+    }
+
+    pub fn answer(&self, phase: Phase) -> Option<&str> {
+        match phase {
+            Phase::Parse => None,
+            Phase::PartOne => self.part1_answer.as_deref(),
+            Phase::PartTwo => self.part2_answer.as_deref(),
+        }
+    }
+
+    /// When the run happened, as a UTC date and time.
+    pub(crate) fn date(&self) -> String {
+        let (days, seconds) = ((self.timestamp / 86_400) as i64, self.timestamp % 86_400);
+        // Converting days since the epoch to a civil date, from
+        // https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = days + 719_468;
+        let (era, day_of_era) = (days.div_euclid(146_097), days.rem_euclid(146_097));
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        format!("{year:04}-{month:02}-{day:02} {:02}:{:02}", seconds / 3_600, seconds % 3_600 / 60)
+    }
+}
+
+/// A phase that took noticeably longer than it usually does.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub phase: Phase,
+    pub time: Duration,
+    pub median: Duration,
+    pub runs: usize,
+}
+impl Regression {
+    pub fn slower_by(&self) -> f64 {
+        percent_change(self.median, self.time)
+    }
+}
+impl Display for Regression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "⚠️ Day {:02}, {} took {}, {:.0}% slower than the median of its last {} runs ({})",
+            self.day,
+            self.phase.to_string().to_lowercase(),
+            self.time.human(Truncate::Nano),
+            self.slower_by(),
+            self.runs,
+            self.median.human(Truncate::Nano),
+        )
+    }
+}
+
+/// Every timed run of a year's solutions, stored next to the inputs as
+/// `input/{year}/history.jsonl` with one [`Record`] per line, so that new
+/// runs only ever get appended.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct History {
+    records: Vec<Record>,
+}
+impl History {
+    pub fn path(year: u16) -> PathBuf {
+        PathBuf::from(format!("input/{}/history.jsonl", year))
+    }
+
+    /// Load the history for a year; a year without a history file simply
+    /// hasn't been timed yet.
+    pub fn load(year: u16) -> Result<Self, AocError> {
+        match fs::read_to_string(Self::path(year)) {
+            Ok(contents) => Self::from_jsonl(&contents),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(AocError::Io(err)),
+        }
+    }
+
+    pub fn from_jsonl(contents: &str) -> Result<Self, AocError> {
+        let records = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line).map_err(|err| AocError::History(format!("line {}: {err}", index + 1)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { records })
+    }
+
+    /// Add a run to the history, and to the end of the year's history file.
+    pub fn append(&mut self, record: Record) -> Result<(), AocError> {
+        let path = Self::path(record.year);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let line = serde_json::to_string(&record).map_err(|err| AocError::History(err.to_string()))?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")?;
+        self.records.push(record);
+        Ok(())
+    }
+
+    /// Every run of a day, oldest first.
+    pub fn runs(&self, day: u8) -> impl Iterator<Item = &Record> {
+        self.records.iter().filter(move |record| record.day == day)
+    }
+
+    /// The most recent run of a day that answered a part.
+    pub fn last_answered(&self, day: u8, part: Phase) -> Option<&Record> {
+        self.runs(day).filter(|record| record.answer(part).is_some()).last()
+    }
+
+    /// The median time of a phase over the most recent runs of a day built
+    /// with the same profile, along with how many runs that covers.
+    pub fn median(&self, day: u8, phase: Phase, profile: &str) -> Option<(Duration, usize)> {
+        let mut times: Vec<Duration> =
+            self.runs(day).filter(|record| record.profile == profile).filter_map(|record| record.time(phase)).collect();
+        times.drain(..times.len().saturating_sub(WINDOW));
+        times.sort();
+        let middle = times.len() / 2;
+        let median = match times.len() {
+            0 => return None,
+            length if length % 2 == 0 => (times[middle - 1] + times[middle]) / 2,
+            _ => times[middle],
+        };
+        Some((median, times.len()))
+    }
+
+    /// Each phase of a new run that is more than `threshold` percent slower
+    /// than the median of the same phase's recent runs (the new run itself
+    /// not included).
+    pub fn regressions(&self, record: &Record, threshold: f64) -> Vec<Regression> {
+        Phase::ALL
+            .into_iter()
+            .filter_map(|phase| {
+                let time = record.time(phase)?;
+                let (median, runs) = self.median(record.day, phase, &record.profile)?;
+                let regression = Regression { day: record.day, phase, time, median, runs };
+                (runs >= MINIMUM_RUNS && regression.slower_by() > threshold).then_some(regression)
+            })
+            .collect()
+    }
+
+    /// A table of the most recent runs of a day, each phase alongside how it
+    /// changed since the previous run with the same profile.
+    pub fn trend(&self, day: u8, last: usize) -> String {
+        let runs: Vec<&Record> = self.runs(day).collect();
+        let rows: Vec<[String; 6]> = runs
+            .iter()
+            .enumerate()
+            .skip(runs.len().saturating_sub(last))
+            .map(|(index, record)| {
+                let previous = runs[..index].iter().rev().find(|previous| previous.profile == record.profile);
+                let phase = |phase: Phase| match (record.time(phase), previous.and_then(|p| p.time(phase))) {
+                    (Some(time), Some(before)) => {
+                        format!("{} ({:+.0}%)", time.human(Truncate::Nano), percent_change(before, time))
+                    },
+                    (Some(time), None) => time.human(Truncate::Nano).to_string(),
+                    (None, _) => "-".to_string(),
+                };
+                [
+                    record.date(),
+                    record.commit.clone().unwrap_or_else(|| "-".to_string()),
+                    record.profile.clone(),
+                    phase(Phase::Parse),
+                    phase(Phase::PartOne),
+                    phase(Phase::PartTwo),
+                ]
+            })
+            .collect();
+        table(&["When (UTC)", "Commit", "Profile", "Parse", "Part 1", "Part 2"], &rows)
+    }
+}
+
+/// How much longer `after` took than `before`, in percent.
+fn percent_change(before: Duration, after: Duration) -> f64 {
+    match before.is_zero() {
+        true => 0.0,
+        false => (after.as_secs_f64() / before.as_secs_f64() - 1.0) * 100.0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: u64, profile: &str, parse_ns: u64, part1_ns: Option<u64>) -> Record {
+        Record {
+            timestamp,
+            year: 2024,
+            day: 1,
+            commit: Some("1a2b3c4".to_string()),
+            profile: profile.to_string(),
+            parse_ns,
+            part1_ns,
+            part2_ns: None,
+            part1_answer: part1_ns.map(|ns| ns.to_string()),
+            part2_answer: None,
+        }
+    }
+
+    fn history() -> History {
+        History {
+            records: vec![
+                record(1_700_000_000, "release", 100, Some(1_000)),
+                record(1_700_000_060, "release", 120, None),
+                record(1_700_000_120, "debug", 5_000, Some(90_000)),
+                record(1_700_000_180, "release", 110, Some(1_200)),
+                record(1_700_000_240, "release", 90, Some(1_100)),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let history = history();
+        let contents: Vec<String> = history.records.iter().map(|r| serde_json::to_string(r).unwrap()).collect();
+        assert!(!contents[1].contains("part1_ns") && !contents[1].contains("part2_ns"));
+        assert_eq!(history, History::from_jsonl(&format!("{}\n\n", contents.join("\n"))).unwrap());
+        assert!(matches!(History::from_jsonl("{\"day\": 1}"), Err(AocError::History(_))));
+    }
+
+    #[test]
+    fn test_median() {
+        let history = history();
+        assert_eq!(Some((Duration::from_nanos(105), 4)), history.median(1, Phase::Parse, "release"));
+        assert_eq!(Some((Duration::from_nanos(1_100), 3)), history.median(1, Phase::PartOne, "release"));
+        assert_eq!(Some((Duration::from_nanos(5_000), 1)), history.median(1, Phase::Parse, "debug"));
+        assert_eq!(None, history.median(1, Phase::PartTwo, "release"));
+        assert_eq!(None, history.median(2, Phase::Parse, "release"));
+    }
+
+    #[test]
+    fn test_last_answered() {
+        let history = history();
+        assert_eq!(Some("1100"), history.last_answered(1, Phase::PartOne).and_then(|run| run.answer(Phase::PartOne)));
+        assert_eq!(None, history.last_answered(1, Phase::PartTwo));
+    }
+
+    #[test]
+    fn test_regressions() {
+        let history = history();
+        let regressions = history.regressions(&record(1_700_000_300, "release", 110, Some(1_500)), 25.0);
+        assert_eq!(1, regressions.len());
+        assert_eq!(Phase::PartOne, regressions[0].phase);
+        assert_eq!(
+            "⚠️ Day 01, part 1 took 1µs 500ns, 36% slower than the median of its last 3 runs (1µs 100ns)",
+            regressions[0].to_string()
+        );
+        // Debug builds have too few runs to compare against.
+        assert!(history.regressions(&record(1_700_000_300, "debug", 9_000, Some(900_000)), 25.0).is_empty());
+    }
+
+    #[test]
+    fn test_trend() {
+        let trend = history().trend(1, 2);
+        let lines: Vec<&str> = trend.lines().collect();
+        assert_eq!(4, lines.len());
+        assert!(lines[2].starts_with("| 2023-11-14 22:16 | 1a2b3c4 | release"));
+        assert!(lines[3].contains("| 90ns (-18%) | 1µs 100ns (-8%) | -"));
+    }
+}
@@ -0,0 +1,29 @@
+//! The normalisation shared by the runners of every year (see [`aoc_input`]),
+//! with errors that point at where in the input something went wrong.
+use crate::ParseError;
+use aoc_input::GridError;
+
+pub use aoc_input::{lines, normalize, normalized, paragraphs};
+
+/// Every line of a rectangular grid of characters, or an error pointing at
+/// the first line that isn't as wide as the first one.
+pub fn grid(input: &str) -> Result<Vec<&str>, ParseError> {
+    aoc_input::grid(input).map_err(|err| match err {
+        GridError::Ragged { offset, .. } => ParseError::at(input, &input[offset..], err.to_string()),
+        GridError::Empty => ParseError::Custom(err.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        assert_eq!(Ok(vec!["ab", "cd"]), grid("\nab\ncd  \n\n"));
+        let err = grid("abc\ndef\ngh\n").unwrap_err();
+        assert!(err.to_string().starts_with("Expected a row of 3 characters like the first, but found 2 at line 3"));
+        assert!(grid("ab\n\ncd").is_err());
+        assert!(grid("\n").is_err());
+    }
+}
@@ -1,100 +1,370 @@
+pub mod alloc;
+mod answers;
 pub mod app;
+pub mod bench;
+pub mod download;
 mod error;
+pub mod example;
+pub mod history;
+pub mod input;
+pub mod registry;
+pub mod report;
+pub mod scaffold;
+pub mod submit;
+pub mod visualize;
 
+use alloc::Allocations;
+pub use answers::{Answers, DayAnswers, Verdict};
 use app::Input;
+use download::{DownloadConfig, Downloader};
 pub use error::*;
 use humanize_duration::{prelude::DurationExt, Truncate};
+use registry::{DynSolution, Erased};
+use std::any::Any;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use std::fs;
+use std::io::{self, Error as IoError, ErrorKind};
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
 use std::time::{Duration, Instant};
 
+/// Both parts borrow the same parsed input, so parsing happens once and never
+/// gets counted towards either part. A part that needs to mutate the parsed
+/// input has to clone it itself.
 pub trait Solution {
-    type Parsed: Clone;
+    type Parsed;
     fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError>;
-    fn part1(&self, input: Self::Parsed) -> Result<impl Display, RunnerError>;
-    fn part2(&self, input: Self::Parsed) -> Result<impl Display, RunnerError>;
+    fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError>;
+    fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError>;
 }
 
+/// Which parts of a solution to run after parsing the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Parts {
+    pub one: bool,
+    pub two: bool,
+}
+impl Parts {
+    pub const BOTH: Self = Self { one: true, two: true };
+    pub const ONE: Self = Self { one: true, two: false };
+    pub const TWO: Self = Self { one: false, two: true };
+    pub const PARSE_ONLY: Self = Self { one: false, two: false };
+}
+impl Default for Parts {
+    fn default() -> Self {
+        Self::BOTH
+    }
+}
+
+/// Stack size for the thread each part runs on. Spawned threads default to a
+/// much smaller stack than the main thread, which recursive solutions outgrow.
+const PART_STACK_SIZE: usize = 8 * 1024 * 1024;
+
 pub struct AdventOfCode {}
 impl AdventOfCode {
+    /// Read the puzzle input from the input cache, downloading it when it's
+    /// missing. Inputs are [`input::normalize`]d as they're loaded (as are
+    /// custom inputs), so solutions see the same text whichever platform or
+    /// editor the file came from.
     fn get_input(year: u16, day: u8) -> Result<Input, AocError> {
         let now = Instant::now();
         let target_path = Path::new(format!("input/{}/{:02}.txt", year, day).as_str()).to_path_buf();
-        let contents =
-            fs::read_to_string(fs::canonicalize(&target_path).unwrap_or(target_path.clone())).map_err(AocError::Io)?;
-        Ok(Input::new(target_path.to_str().unwrap().to_string(), contents, now.elapsed()))
+        let (contents, allocations) = alloc::measure(|| {
+            match fs::read_to_string(fs::canonicalize(&target_path).unwrap_or(target_path.clone())) {
+                Ok(contents) => Ok(contents),
+                Err(err) if err.kind() == ErrorKind::NotFound => Self::download_input(year, day, &target_path, err),
+                Err(err) => Err(AocError::Io(err)),
+            }
+            .map(input::normalized)
+        });
+        let input = Input::new(year, day, target_path.to_str().unwrap().to_string(), contents?, now.elapsed());
+        Ok(input.allocated(allocations))
     }
 
-    pub fn run<S: Solution>(day: S, input: Input) -> Result<DayResult, AocError> {
+    /// Read an input from somewhere other than the puzzle input cache, such as
+    /// an example copied from the puzzle description. Use `-` for stdin.
+    fn get_custom_input(year: u16, day: u8, path: &str) -> Result<Input, AocError> {
         let now = Instant::now();
-        let parsed = day.parse(input.contents())?;
-        let parse_time = now.elapsed();
+        let (contents, allocations) = alloc::measure(|| match path {
+            "-" => io::read_to_string(io::stdin()).map(|contents| ("<stdin>".to_string(), contents)),
+            path => fs::read_to_string(path).map(|contents| (path.to_string(), contents)),
+        }
+        .map(|(filepath, contents)| (filepath, input::normalized(contents))));
+        let (filepath, contents) = contents?;
+        Ok(Input::new(year, day, filepath, contents, now.elapsed()).custom().allocated(allocations))
+    }
 
-        let part_one_parsed = parsed.clone();
-        let now = Instant::now();
-        let part_one_result = day.part1(part_one_parsed);
-        let part_one_time = now.elapsed();
+    /// Fetch a missing input and cache it at the path it was expected to be
+    /// found at, so that it only ever gets downloaded once. When downloading
+    /// hasn't been configured, the original "file not found" error is kept.
+    fn download_input(year: u16, day: u8, target_path: &Path, not_found: IoError) -> Result<String, AocError> {
+        let config = DownloadConfig::load()?;
+        if !config.is_enabled() {
+            return Err(AocError::Io(not_found));
+        }
+        let cache_directory = target_path.parent().unwrap_or(Path::new("."));
+        let contents = Downloader::new(config, cache_directory).fetch(year, day)?;
+        fs::create_dir_all(cache_directory)?;
+        fs::write(target_path, &contents)?;
+        Ok(contents)
+    }
+
+    pub fn run<S>(day: S, input: Input) -> Result<DayResult, AocError>
+    where
+        S: Solution + Send + Sync + 'static,
+        S::Parsed: Send + Sync + 'static,
+    {
+        Self::run_dyn(Arc::new(day), input)
+    }
+
+    pub fn run_dyn(day: Arc<dyn DynSolution>, input: Input) -> Result<DayResult, AocError> {
+        let answers = Answers::load_or_unchecked(input.year());
+        Self::run_parts(day, input, Parts::BOTH, None, &answers)
+    }
 
-        let part_two_parsed = parsed.clone();
+    /// Parse the input and run the selected parts, giving up on any part that
+    /// is still running once the timeout (if any) has elapsed. Each part is
+    /// checked against the known answers for the day, if there are any.
+    pub fn run_parts(
+        day: Arc<dyn DynSolution>,
+        input: Input,
+        parts: Parts,
+        timeout: Option<Duration>,
+        answers: &Answers,
+    ) -> Result<DayResult, AocError> {
         let now = Instant::now();
-        let part_two_result = day.part2(part_two_parsed);
-        let part_two_time = now.elapsed();
+        let (parsed, parse_allocations) = alloc::measure(|| day.parse(input.contents()));
+        let parsed: Arc<Erased> = Arc::new(parsed?);
+        let parse_time = now.elapsed();
+
+        let part_one = match parts.one {
+            true => {
+                let (day, parsed) = (Arc::clone(&day), Arc::clone(&parsed));
+                Self::supervise(move || day.part1(&parsed), timeout)
+            },
+            false => (RunResult::Skipped, Duration::ZERO, None),
+        };
+        let part_two = match parts.two {
+            true => {
+                let (day, parsed) = (Arc::clone(&day), Arc::clone(&parsed));
+                Self::supervise(move || day.part2(&parsed), timeout)
+            },
+            false => (RunResult::Skipped, Duration::ZERO, None),
+        };
 
+        // Answers are only known for the puzzle input, not for any examples.
+        let expected = match input.is_custom() {
+            true => DayAnswers::default(),
+            false => answers.get(input.day()).cloned().unwrap_or_default(),
+        };
         Ok(DayResult {
             input,
             parse_time,
-            part_one: PartResult {
-                time: part_one_time,
-                answer: part_one_result
-                    .map(|result| RunResult::Success(result.to_string()))
-                    .unwrap_or_else(RunResult::Fail),
+            parse_allocations,
+            part_one: PartResult::new(part_one, expected.part1.as_ref()),
+            part_two: PartResult::new(part_two, expected.part2.as_ref()),
+        })
+    }
+
+    /// Run, time and count the allocations of a part on its own thread, so
+    /// that a part that panics or never finishes gets reported instead of
+    /// taking the whole runner down with it.
+    ///
+    /// A part that times out can't be stopped, only abandoned: it keeps
+    /// running (and using a CPU) in the background until the process exits,
+    /// which slows down and skews the timings of anything that runs after it,
+    /// including the other days when running several at once.
+    ///
+    /// Panics can only be caught when unwinding: with `panic = "abort"` (as
+    /// in the release profile) a panicking part still aborts the process.
+    fn supervise(
+        part: impl FnOnce() -> Result<String, RunnerError> + Send + 'static,
+        timeout: Option<Duration>,
+    ) -> (RunResult, Duration, Option<Allocations>) {
+        let (sender, receiver) = mpsc::channel();
+        let spawned = thread::Builder::new().name("part".to_string()).stack_size(PART_STACK_SIZE).spawn(move || {
+            let now = Instant::now();
+            let (result, allocations) = alloc::measure(|| panic::catch_unwind(AssertUnwindSafe(part)));
+            let elapsed = now.elapsed();
+            // Nobody is listening any more if the part has already timed out.
+            sender.send((result, elapsed, allocations)).ok();
+        });
+        if let Err(err) = spawned {
+            return (RunResult::Fail(RunnerError::Other(Box::new(err))), Duration::ZERO, None);
+        }
+        // Waiting for longer than an `Instant` can represent waits forever.
+        let limit = timeout.unwrap_or(Duration::MAX);
+        match receiver.recv_timeout(limit) {
+            Ok((Ok(result), elapsed, allocations)) => {
+                (result.map(RunResult::Success).unwrap_or_else(RunResult::Fail), elapsed, allocations)
             },
-            part_two: PartResult {
-                time: part_two_time,
-                answer: part_two_result
-                    .map(|result| RunResult::Success(result.to_string()))
-                    .unwrap_or_else(RunResult::Fail),
+            Ok((Err(payload), elapsed, allocations)) => {
+                (RunResult::Panicked(Self::panic_message(payload)), elapsed, allocations)
             },
-        })
+            Err(RecvTimeoutError::Timeout) => (RunResult::Timeout(limit), limit, None),
+            Err(RecvTimeoutError::Disconnected) => {
+                (RunResult::Panicked("Part exited without an answer".to_string()), Duration::ZERO, None)
+            },
+        }
+    }
+
+    fn panic_message(payload: Box<dyn Any + Send>) -> String {
+        match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "Unknown panic payload".to_string(),
+            },
+        }
     }
 }
 
-enum RunResult {
+pub(crate) enum RunResult {
     Success(String),
     Fail(RunnerError),
+    Skipped,
+    Timeout(Duration),
+    Panicked(String),
+}
+impl RunResult {
+    /// Whether the part ran but didn't produce an answer. Parts that haven't
+    /// been implemented yet are expected not to produce one.
+    pub(crate) fn is_failure(&self) -> bool {
+        match self {
+            Self::Fail(RunnerError::Unimplemented) | Self::Success(_) | Self::Skipped => false,
+            Self::Fail(_) | Self::Timeout(_) | Self::Panicked(_) => true,
+        }
+    }
 }
-struct PartResult {
+impl Display for RunResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Success(answer) => write!(f, "{answer}"),
+            Self::Skipped => write!(f, "⏭️ Skipped"),
+            Self::Timeout(limit) => write!(
+                f,
+                "⏱️ Timed out after {} (still running, so later timings may be slower)",
+                limit.human(Truncate::Nano)
+            ),
+            Self::Panicked(message) => write!(f, "💥 Panicked: {message}"),
+            Self::Fail(err) => write!(f, "💥 {} error: {}", err.kind(), Chain(err)),
+        }
+    }
+}
+
+pub(crate) struct PartResult {
     answer: RunResult,
     time: Duration,
+    allocations: Option<Allocations>,
+    verdict: Verdict,
+}
+impl PartResult {
+    fn new((answer, time, allocations): (RunResult, Duration, Option<Allocations>), expected: Option<&String>) -> Self {
+        let verdict = Verdict::check(expected, &answer);
+        Self { answer, time, allocations, verdict }
+    }
 }
 pub struct DayResult {
     input: Input,
     parse_time: Duration,
+    parse_allocations: Option<Allocations>,
     part_one: PartResult,
     part_two: PartResult,
 }
+impl DayResult {
+    /// Combined time spent loading the input, parsing it and running both parts.
+    pub fn total_time(&self) -> Duration {
+        self.input.elapsed() + self.parse_time + self.part_one.time + self.part_two.time
+    }
+
+    /// Exit code flags for this result: whether either part has regressed, and
+    /// which parts failed (parts that haven't been implemented yet don't count).
+    pub fn exit_code(&self) -> i32 {
+        let mut code = 0;
+        if self.has_regression() {
+            code |= app::EXIT_FAILURE;
+        }
+        if self.part_one.answer.is_failure() {
+            code |= app::EXIT_PART_ONE_FAILED;
+        }
+        if self.part_two.answer.is_failure() {
+            code |= app::EXIT_PART_TWO_FAILED;
+        }
+        code
+    }
+
+    /// Whether either part no longer matches its previously accepted answer.
+    pub fn has_regression(&self) -> bool {
+        self.part_one.verdict.is_incorrect() || self.part_two.verdict.is_incorrect()
+    }
+}
 impl Display for DayResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let part = |number: u8, part: &PartResult| match part.answer {
+            RunResult::Skipped => format!("⭐ Part {number}: {}", part.answer),
+            _ => {
+                let usage = usage(part.time, part.allocations);
+                format!("⭐ Part {number} ({usage}): {} {}", part.answer, part.verdict)
+            },
+        };
         writeln!(
             f,
             r#"{}
 Parsed in: {}
 
-⭐ Part 1 ({}): {}
-⭐ Part 2 ({}): {}"#,
+{}
+{}"#,
             self.input,
-            self.parse_time.human(Truncate::Nano),
-            self.part_one.time.human(Truncate::Nano),
-            match &self.part_one.answer {
-                RunResult::Success(result) => result,
-                RunResult::Fail(_err) => "fuck a duck! an error occured!",
-            },
-            self.part_two.time.human(Truncate::Nano),
-            match &self.part_two.answer {
-                RunResult::Success(result) => result,
-                RunResult::Fail(_err) => "fuck a duck! an error occured!",
-            },
+            usage(self.parse_time, self.parse_allocations),
+            part(1, &self.part_one),
+            part(2, &self.part_two),
         )
     }
 }
+
+/// How long a phase took, followed by its allocations when they were counted.
+pub(crate) fn usage(time: Duration, allocations: Option<Allocations>) -> String {
+    match allocations {
+        Some(allocations) => format!("{}; {allocations}", time.human(Truncate::Nano)),
+        None => time.human(Truncate::Nano).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supervise_answer() {
+        let (result, _, _) = AdventOfCode::supervise(|| Ok("42".to_string()), Some(Duration::from_secs(5)));
+        assert!(matches!(result, RunResult::Success(answer) if answer == "42"));
+    }
+
+    #[test]
+    fn test_supervise_timeout() {
+        let limit = Duration::from_millis(20);
+        let (result, time, allocations) = AdventOfCode::supervise(
+            || {
+                thread::sleep(Duration::from_secs(5));
+                Ok(String::new())
+            },
+            Some(limit),
+        );
+        assert!(matches!(result, RunResult::Timeout(timeout) if timeout == limit));
+        assert_eq!(limit, time);
+        assert_eq!(None, allocations);
+        assert!(result.is_failure());
+    }
+
+    #[test]
+    fn test_supervise_panic() {
+        let (result, _, _) = AdventOfCode::supervise(|| panic!("index out of bounds"), None);
+        assert!(matches!(&result, RunResult::Panicked(message) if message == "index out of bounds"));
+        let (result, _, _) = AdventOfCode::supervise(|| panic!("{} out of bounds", 3), None);
+        assert!(matches!(&result, RunResult::Panicked(message) if message == "3 out of bounds"));
+        assert!(result.is_failure());
+    }
+}
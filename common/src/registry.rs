@@ -0,0 +1,163 @@
+use crate::visualize::{Diagram, Visualize};
+use crate::{AocError, ParseError, RunnerError, Solution};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Parsed state of a solution, with its concrete type erased.
+pub type Erased = Box<dyn Any + Send + Sync>;
+
+/// Object-safe counterpart to [`Solution`], so that every day of a year can
+/// be stored side-by-side despite each using a different `Parsed` type.
+///
+/// Every [`Solution`] gets this for free; the erased state handed to each
+/// part must have come from the same solution's `parse`. Parts are run on
+/// their own thread while sharing the parsed state, so solutions and their
+/// parsed state must be [`Send`] and [`Sync`].
+pub trait DynSolution: Send + Sync {
+    fn parse(&self, input: &str) -> Result<Erased, ParseError>;
+    fn part1(&self, parsed: &Erased) -> Result<String, RunnerError>;
+    fn part2(&self, parsed: &Erased) -> Result<String, RunnerError>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Send + Sync,
+    S::Parsed: Send + Sync + 'static,
+{
+    fn parse(&self, input: &str) -> Result<Erased, ParseError> {
+        Solution::parse(self, input).map(|parsed| Box::new(parsed) as Erased)
+    }
+
+    fn part1(&self, parsed: &Erased) -> Result<String, RunnerError> {
+        Solution::part1(self, parsed.downcast_ref().expect(MISMATCH)).map(|answer| answer.to_string())
+    }
+
+    fn part2(&self, parsed: &Erased) -> Result<String, RunnerError> {
+        Solution::part2(self, parsed.downcast_ref().expect(MISMATCH)).map(|answer| answer.to_string())
+    }
+}
+
+const MISMATCH: &str = "Erased state passed to a different solution than the one that parsed it";
+
+/// Every solution available for a year, keyed by day number, along with any
+/// visualizations or diagrams of them.
+#[derive(Default)]
+pub struct Registry {
+    solutions: BTreeMap<u8, Arc<dyn DynSolution>>,
+    visualizations: BTreeMap<u8, Arc<dyn Visualize>>,
+    diagrams: BTreeMap<u8, Arc<dyn Diagram>>,
+}
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, day: u8, solution: impl DynSolution + 'static) {
+        self.solutions.insert(day, Arc::new(solution));
+    }
+
+    pub fn days(&self) -> Vec<u8> {
+        self.solutions.keys().copied().collect()
+    }
+
+    pub fn get(&self, day: u8) -> Result<Arc<dyn DynSolution>, AocError> {
+        self.solutions.get(&day).cloned().ok_or_else(|| AocError::OutOfScope(day, self.days()))
+    }
+
+    pub fn register_visualization(&mut self, day: u8, visualization: impl Visualize + 'static) {
+        self.visualizations.insert(day, Arc::new(visualization));
+    }
+
+    pub fn visualized_days(&self) -> Vec<u8> {
+        self.visualizations.keys().copied().collect()
+    }
+
+    pub fn visualization(&self, day: u8) -> Option<Arc<dyn Visualize>> {
+        self.visualizations.get(&day).cloned()
+    }
+
+    pub fn register_diagram(&mut self, day: u8, diagram: impl Diagram + 'static) {
+        self.diagrams.insert(day, Arc::new(diagram));
+    }
+
+    pub fn diagrammed_days(&self) -> Vec<u8> {
+        self.diagrams.keys().copied().collect()
+    }
+
+    pub fn diagram(&self, day: u8) -> Option<Arc<dyn Diagram>> {
+        self.diagrams.get(&day).cloned()
+    }
+}
+
+/// Build a [`Registry`] from a list of `day => solution` pairs.
+///
+/// ```rust
+/// use aoc_common::{register_days, ParseError, RunnerError, Solution};
+///
+/// struct Day01 {}
+/// impl Solution for Day01 {
+///     type Parsed = usize;
+///     fn parse(&self, input: &str) -> Result<usize, ParseError> {
+///         Ok(input.len())
+///     }
+///     fn part1(&self, input: &usize) -> Result<impl std::fmt::Display, RunnerError> {
+///         Ok(*input)
+///     }
+///     fn part2(&self, input: &usize) -> Result<impl std::fmt::Display, RunnerError> {
+///         Ok(input * 2)
+///     }
+/// }
+///
+/// let registry = register_days! {
+///     1 => Day01 {},
+/// };
+/// assert_eq!(vec![1], registry.days());
+/// ```
+#[macro_export]
+macro_rules! register_days {
+    ($($day:literal => $solution:expr),* $(,)?) => {{
+        let mut registry = $crate::registry::Registry::new();
+        $(registry.register($day, $solution);)*
+        registry
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Display;
+
+    struct Lines {}
+    impl Solution for Lines {
+        type Parsed = Vec<String>;
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed, ParseError> {
+            Ok(input.lines().map(str::to_string).collect())
+        }
+
+        fn part1(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+            Ok(input.len())
+        }
+
+        fn part2(&self, input: &Self::Parsed) -> Result<impl Display, RunnerError> {
+            Ok(input.join("+"))
+        }
+    }
+
+    #[test]
+    fn test_erased_solution() {
+        let registry = register_days! { 3 => Lines {} };
+        let solution = registry.get(3).unwrap();
+        let parsed = solution.parse("a\nb\nc").unwrap();
+        assert_eq!("3", solution.part1(&parsed).unwrap());
+        assert_eq!("a+b+c", solution.part2(&parsed).unwrap());
+    }
+
+    #[test]
+    fn test_unregistered_day() {
+        let registry = register_days! { 1 => Lines {}, 2 => Lines {} };
+        assert_eq!(vec![1, 2], registry.days());
+        assert!(matches!(registry.get(5), Err(AocError::OutOfScope(5, days)) if days == vec![1, 2]));
+    }
+}
@@ -0,0 +1,301 @@
+use crate::alloc::Allocations;
+use crate::{AocError, DayResult, Location, ParseError, PartResult, RunResult, RunnerError, Verdict};
+use serde::Serialize;
+use std::time::Duration;
+
+/// How results should be written to standard output.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+impl Format {
+    pub(crate) const NAMES: [&'static str; 3] = ["text", "json", "csv"];
+
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!("`{value}` is not one of: {}", Self::NAMES.join(", "))),
+        }
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ErrorReport {
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<Box<ErrorReport>>,
+}
+impl ErrorReport {
+    fn new(kind: &'static str, message: String, source: Option<ErrorReport>) -> Self {
+        Self {
+            kind,
+            message,
+            location: None,
+            source: source.map(Box::new),
+        }
+    }
+}
+impl From<&ParseError> for ErrorReport {
+    fn from(err: &ParseError) -> Self {
+        match err {
+            ParseError::Syntax { message, location } => Self {
+                location: Some(location.clone()),
+                ..Self::new(err.kind(), message.to_owned(), None)
+            },
+            _ => Self::new(err.kind(), err.to_string(), None),
+        }
+    }
+}
+impl From<&RunnerError> for ErrorReport {
+    fn from(err: &RunnerError) -> Self {
+        Self::new(err.kind(), err.to_string(), None)
+    }
+}
+impl From<&AocError> for ErrorReport {
+    fn from(err: &AocError) -> Self {
+        let source = match err {
+            AocError::Parse(err) => Some(err.into()),
+            AocError::Run(err) => Some(err.into()),
+            _ => None,
+        };
+        Self::new(err.kind(), err.to_string(), source)
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct InputReport {
+    path: String,
+    bytes: usize,
+    load_time_ns: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allocations: Option<Allocations>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PartReport {
+    answer: Option<String>,
+    time_ns: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    allocations: Option<Allocations>,
+    verdict: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expected: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
+}
+impl From<&PartResult> for PartReport {
+    fn from(part: &PartResult) -> Self {
+        let (answer, error) = match &part.answer {
+            RunResult::Success(answer) => (Some(answer.to_owned()), None),
+            RunResult::Fail(err) => (None, Some(err.into())),
+            RunResult::Skipped => (None, None),
+            RunResult::Timeout(_) => (None, Some(ErrorReport::new("Timeout", part.answer.to_string(), None))),
+            RunResult::Panicked(message) => (None, Some(ErrorReport::new("Panic", message.to_owned(), None))),
+        };
+        let (verdict, expected) = match (&part.answer, &part.verdict) {
+            (RunResult::Skipped, _) => ("skipped", None),
+            (_, Verdict::Correct) => ("correct", None),
+            (_, Verdict::Incorrect { expected }) => ("incorrect", Some(expected.to_owned())),
+            (_, Verdict::Unknown) => ("unknown", None),
+        };
+        Self {
+            answer,
+            time_ns: nanos(part.time),
+            allocations: part.allocations,
+            verdict,
+            expected,
+            error,
+        }
+    }
+}
+
+/// Machine-readable representation of running a single day, whether it
+/// produced a result or failed before either part could run.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DayReport {
+    year: u16,
+    day: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input: Option<InputReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_time_ns: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parse_allocations: Option<Allocations>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part1: Option<PartReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part2: Option<PartReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorReport>,
+}
+impl DayReport {
+    pub fn new(year: u16, day: u8, result: &Result<DayResult, AocError>) -> Self {
+        match result {
+            Ok(result) => Self {
+                year,
+                day,
+                input: Some(InputReport {
+                    path: result.input.filepath().to_owned(),
+                    bytes: result.input.length(),
+                    load_time_ns: nanos(result.input.elapsed()),
+                    allocations: result.input.allocations(),
+                }),
+                parse_time_ns: Some(nanos(result.parse_time)),
+                parse_allocations: result.parse_allocations,
+                part1: Some((&result.part_one).into()),
+                part2: Some((&result.part_two).into()),
+                error: None,
+            },
+            Err(err) => Self {
+                year,
+                day,
+                input: None,
+                parse_time_ns: None,
+                parse_allocations: None,
+                part1: None,
+                part2: None,
+                error: Some(err.into()),
+            },
+        }
+    }
+}
+
+pub fn to_json(reports: &[DayReport]) -> String {
+    serde_json::to_string_pretty(reports).expect("Reports only contain types that serialize to JSON")
+}
+
+pub fn to_csv(reports: &[DayReport]) -> String {
+    const HEADER: &str = "year,day,input_path,input_bytes,input_ns,parse_ns,\
+        part1_answer,part1_ns,part1_verdict,part1_expected,part1_error,\
+        part2_answer,part2_ns,part2_verdict,part2_expected,part2_error,error";
+
+    fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+    fn optional<T: ToString>(value: Option<T>) -> String {
+        value.map(|v| escape(&v.to_string())).unwrap_or_default()
+    }
+    fn error(error: &Option<ErrorReport>) -> String {
+        optional(error.as_ref().map(|e| format!("{}: {}", e.kind, e.message)))
+    }
+    fn part(part: &Option<PartReport>) -> [String; 5] {
+        match part {
+            Some(part) => [
+                optional(part.answer.as_ref()),
+                part.time_ns.to_string(),
+                part.verdict.to_string(),
+                optional(part.expected.as_ref()),
+                error(&part.error),
+            ],
+            None => Default::default(),
+        }
+    }
+
+    let mut lines = vec![HEADER.to_string()];
+    for report in reports {
+        let input = report.input.as_ref();
+        let mut fields = vec![
+            report.year.to_string(),
+            report.day.to_string(),
+            optional(input.map(|i| &i.path)),
+            optional(input.map(|i| i.bytes)),
+            optional(input.map(|i| i.load_time_ns)),
+            optional(report.parse_time_ns),
+        ];
+        fields.extend(part(&report.part1));
+        fields.extend(part(&report.part2));
+        fields.push(error(&report.error));
+        lines.push(fields.join(","));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> DayReport {
+        DayReport {
+            year: 2024,
+            day: 1,
+            input: Some(InputReport {
+                path: "input/2024/01.txt".to_string(),
+                bytes: 36,
+                load_time_ns: 1200,
+                allocations: None,
+            }),
+            parse_time_ns: Some(3400),
+            parse_allocations: Some(Allocations { count: 2, bytes: 96, peak: 64 }),
+            part1: Some(PartReport {
+                answer: Some("11".to_string()),
+                time_ns: 560,
+                allocations: None,
+                verdict: "correct",
+                expected: None,
+                error: None,
+            }),
+            part2: Some(PartReport {
+                answer: None,
+                time_ns: 78,
+                allocations: None,
+                verdict: "unknown",
+                expected: None,
+                error: Some(ErrorReport::new("Custom", "bad, \"really\" bad".to_string(), None)),
+            }),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(Ok(Format::Json), Format::parse("JSON"));
+        assert_eq!(Ok(Format::Csv), Format::parse("csv"));
+        assert!(Format::parse("xml").is_err());
+    }
+
+    #[test]
+    fn test_error_report() {
+        let err = AocError::Parse(ParseError::Custom("oops".to_string()));
+        let report = ErrorReport::from(&err);
+        assert_eq!("Parse", report.kind);
+        assert_eq!(Some(Box::new(ErrorReport::new("Custom", "oops".to_string(), None))), report.source);
+    }
+
+    #[test]
+    fn test_json() {
+        let json: serde_json::Value = serde_json::from_str(&to_json(&[report()])).unwrap();
+        assert_eq!(3400, json[0]["parse_time_ns"]);
+        assert_eq!(64, json[0]["parse_allocations"]["peak"]);
+        assert!(json[0]["part1"].get("allocations").is_none());
+        assert_eq!("11", json[0]["part1"]["answer"]);
+        assert_eq!("Custom", json[0]["part2"]["error"]["kind"]);
+        assert!(json[0].get("error").is_none());
+    }
+
+    #[test]
+    fn test_csv() {
+        let csv = to_csv(&[report()]);
+        let mut lines = csv.lines();
+        assert_eq!(17, lines.next().unwrap().split(',').count());
+        assert_eq!(
+            r#"2024,1,input/2024/01.txt,36,1200,3400,11,560,correct,,,,78,unknown,,"Custom: bad, ""really"" bad","#,
+            lines.next().unwrap()
+        );
+    }
+}
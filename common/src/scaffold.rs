@@ -0,0 +1,294 @@
+use crate::AocError;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a year's solutions are organised, which decides what gets generated
+/// for a new day and where it has to be registered.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Layout {
+    /// A crate per day, with a standalone `process` function for each part.
+    ProcessCrates,
+    /// A module per day in the year's crate, implementing [`crate::Solution`].
+    Modules,
+    /// A crate per day, implementing the year's own `Solution` trait.
+    SolutionCrates,
+}
+impl Layout {
+    pub fn of(year: u16) -> Option<Self> {
+        match year {
+            2023 => Some(Self::ProcessCrates),
+            2024 => Some(Self::Modules),
+            2025 => Some(Self::SolutionCrates),
+            _ => None,
+        }
+    }
+}
+
+/// A file that generating a day would create, or the new contents of a file
+/// that the day has to be registered in.
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    Create(PathBuf, String),
+    Update(PathBuf, String),
+}
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Create(path, _) => write!(f, "Created {}", path.display()),
+            Self::Update(path, _) => write!(f, "Registered in {}", path.display()),
+        }
+    }
+}
+
+/// Generates a new day from the template of its year's layout:
+///
+/// - 2023: copies `2023/day-template` to a new crate, and adds it to the
+///   `aoc` runner's dependencies and registry.
+/// - 2024: renders `2024/src/day-template.rs` to a new module with an empty
+///   example fixture, and adds it to the year's registry.
+/// - 2025: copies `2025/day-template` to a new crate, and adds it to the
+///   `aoc` runner's dependencies and registry.
+///
+/// The new crates are picked up by their year's workspace on their own.
+pub struct Scaffold {
+    root: PathBuf,
+    year: u16,
+    day: u8,
+    layout: Layout,
+}
+impl Scaffold {
+    /// Placeholder for the zero-padded day number in templates.
+    const PLACEHOLDER: &'static str = "XX";
+
+    pub fn new(root: impl Into<PathBuf>, year: u16, day: u8) -> Result<Self, AocError> {
+        let layout = Layout::of(year).ok_or_else(|| AocError::Scaffold(format!("There is no template for {year}")))?;
+        if day > 25 {
+            return Err(AocError::Scaffold(format!("Day #{day} is not a day of Advent")));
+        }
+        Ok(Self { root: root.into(), year, day, layout })
+    }
+
+    /// Work out every change needed for the new day, without touching any
+    /// file. Fails if the day already exists, or is already registered.
+    pub fn plan(&self) -> Result<Vec<Change>, AocError> {
+        let (year, day, padded) = (self.year, self.day, format!("{:02}", self.day));
+        let mut changes = Vec::new();
+        match self.layout {
+            Layout::Modules => {
+                let module = format!("day{padded}");
+                changes.push(Change::Create(
+                    Path::new("2024/src").join(format!("{module}.rs")),
+                    self.render(&self.read(Path::new("2024/src/day-template.rs"))?),
+                ));
+                changes.push(Change::Create(
+                    Path::new("examples/2024").join(&padded).join("example.txt"),
+                    "# Paste the worked example from the puzzle below the `---` line, along with\n\
+                     # its answers as `part1: …` and `part2: …`.\n---\n"
+                        .to_string(),
+                ));
+                let lib = Path::new("2024/src/lib.rs");
+                let contents = self.register(lib, &self.read(lib)?, module_entry, &format!("pub mod {module};"))?;
+                let contents =
+                    self.register(lib, &contents, arm_entry, &format!("{day} => {module}::Day{padded} {{}},"))?;
+                changes.push(Change::Update(lib.to_path_buf(), contents));
+            },
+            Layout::ProcessCrates | Layout::SolutionCrates => {
+                let directory = PathBuf::from(year.to_string());
+                changes.extend(
+                    self.render_directory(&directory.join("day-template"), &directory.join(format!("day{padded}")))?,
+                );
+
+                // How the runner's dependency on the crate is named.
+                let krate = format!("aoc_{year}_day{padded}");
+                let (package, arm) = match self.layout {
+                    Layout::ProcessCrates => (
+                        format!("aoc-{year}-day{padded}"),
+                        format!("{day} => Process::new({krate}::part1::process, {krate}::part2::process),"),
+                    ),
+                    _ => (format!("day{padded}"), format!("{day} => Modern::<{krate}::Day{padded}>::default(),")),
+                };
+                let manifest = Path::new("aoc/Cargo.toml");
+                let prefix = format!("aoc-{year}-day");
+                let dependency =
+                    format!("{prefix}{padded} = {{ package = \"{package}\", path = \"../{year}/day{padded}\" }}");
+                let contents = self.register(
+                    manifest,
+                    &self.read(manifest)?,
+                    |line| line.strip_prefix(&prefix)?.split_once(' ')?.0.parse().ok(),
+                    &dependency,
+                )?;
+                changes.push(Change::Update(manifest.to_path_buf(), contents));
+
+                let registry = PathBuf::from(format!("aoc/src/y{year}.rs"));
+                let contents = self.register(&registry, &self.read(&registry)?, arm_entry, &arm)?;
+                changes.push(Change::Update(registry, contents));
+            },
+        }
+        for change in &changes {
+            if let Change::Create(path, _) = change {
+                if self.root.join(path).exists() {
+                    return Err(AocError::Scaffold(format!("{} already exists", path.display())));
+                }
+            }
+        }
+        Ok(changes)
+    }
+
+    /// Generate the new day. Nothing is written unless every change could be
+    /// planned, so a day that already exists is left untouched.
+    pub fn generate(&self) -> Result<Vec<Change>, AocError> {
+        let changes = self.plan()?;
+        for change in &changes {
+            let (Change::Create(path, contents) | Change::Update(path, contents)) = change;
+            let path = self.root.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+        Ok(changes)
+    }
+
+    fn read(&self, path: &Path) -> Result<String, AocError> {
+        fs::read_to_string(self.root.join(path))
+            .map_err(|err| AocError::Scaffold(format!("Could not read {}: {err}", path.display())))
+    }
+
+    fn render(&self, template: &str) -> String {
+        template.replace(Self::PLACEHOLDER, &format!("{:02}", self.day))
+    }
+
+    /// Render every file in a template directory (and its file names).
+    fn render_directory(&self, template: &Path, target: &Path) -> Result<Vec<Change>, AocError> {
+        let entries = fs::read_dir(self.root.join(template))
+            .map_err(|err| AocError::Scaffold(format!("Could not read {}: {err}", template.display())))?;
+        let mut changes = Vec::new();
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == "target" || name == "Cargo.lock" {
+                continue;
+            }
+            let (source, destination) = (template.join(&name), target.join(self.render(&name)));
+            match entry.file_type()?.is_dir() {
+                true => changes.extend(self.render_directory(&source, &destination)?),
+                false => changes.push(Change::Create(destination, self.render(&self.read(&source)?))),
+            }
+        }
+        changes.sort_by(|a, b| format!("{a}").cmp(&format!("{b}")));
+        Ok(changes)
+    }
+
+    /// Insert `line` among the entries recognised by `entry` (which returns
+    /// the day of an entry), keeping them in order of day and indented like
+    /// their neighbours.
+    fn register(
+        &self,
+        path: &Path,
+        contents: &str,
+        entry: impl Fn(&str) -> Option<u8>,
+        line: &str,
+    ) -> Result<String, AocError> {
+        let mut lines: Vec<&str> = contents.lines().collect();
+        let entries: Vec<(usize, u8)> =
+            lines.iter().enumerate().filter_map(|(index, line)| Some((index, entry(line.trim())?))).collect();
+        if entries.iter().any(|(_, day)| *day == self.day) {
+            return Err(AocError::Scaffold(format!("Day #{} is already registered in {}", self.day, path.display())));
+        }
+        let (neighbour, index) = match entries.iter().find(|(_, day)| *day > self.day) {
+            Some((index, _)) => (*index, *index),
+            None => match entries.last() {
+                Some((index, _)) => (*index, index + 1),
+                None => {
+                    return Err(AocError::Scaffold(format!("Could not find where to register in {}", path.display())))
+                },
+            },
+        };
+        let indentation = &lines[neighbour][..lines[neighbour].len() - lines[neighbour].trim_start().len()];
+        let line = format!("{indentation}{line}");
+        lines.insert(index, &line);
+        let mut contents = lines.join("\n");
+        contents.push('\n');
+        Ok(contents)
+    }
+}
+
+fn module_entry(line: &str) -> Option<u8> {
+    line.strip_prefix("pub mod day")?.strip_suffix(';')?.parse().ok()
+}
+
+/// A match arm of `register_days!`.
+fn arm_entry(line: &str) -> Option<u8> {
+    line.split_once(" => ")?.0.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> &'static Path {
+        Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap()
+    }
+
+    #[test]
+    fn test_register() {
+        let scaffold = Scaffold::new(".", 2024, 3).unwrap();
+        let path = Path::new("lib.rs");
+        let contents = "pub mod day01;\npub mod day04;\n\nfn solutions() {\n    1 => day01::Day01 {},\n}\n";
+        let contents = scaffold.register(path, contents, module_entry, "pub mod day03;").unwrap();
+        let contents = scaffold.register(path, &contents, arm_entry, "3 => day03::Day03 {},").unwrap();
+        assert_eq!(
+            "pub mod day01;\npub mod day03;\npub mod day04;\n\n\
+             fn solutions() {\n    1 => day01::Day01 {},\n    3 => day03::Day03 {},\n}\n",
+            contents
+        );
+        assert!(scaffold.register(path, &contents, module_entry, "pub mod day03;").is_err());
+        assert!(scaffold.register(path, "", module_entry, "pub mod day03;").is_err());
+    }
+
+    fn created<'a>(changes: &'a [Change], path: &str) -> Option<&'a str> {
+        changes.iter().find_map(|change| match change {
+            Change::Create(p, contents) if p == Path::new(path) => Some(contents.as_str()),
+            _ => None,
+        })
+    }
+
+    fn updated<'a>(changes: &'a [Change], path: &str) -> Option<&'a str> {
+        changes.iter().find_map(|change| match change {
+            Change::Update(p, contents) if p == Path::new(path) => Some(contents.as_str()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_plan_module() {
+        let changes = Scaffold::new(root(), 2024, 25).unwrap().plan().unwrap();
+        let module = created(&changes, "2024/src/day25.rs").unwrap();
+        assert!(module.contains("pub struct Day25 {}") && !module.contains(Scaffold::PLACEHOLDER));
+        assert!(created(&changes, "examples/2024/25/example.txt").is_some());
+        let lib = updated(&changes, "2024/src/lib.rs").unwrap();
+        assert!(lib.contains("pub mod day25;\n") && lib.contains("25 => day25::Day25 {},\n"));
+    }
+
+    #[test]
+    fn test_plan_crate() {
+        let changes = Scaffold::new(root(), 2025, 24).unwrap().plan().unwrap();
+        assert!(created(&changes, "2025/day24/Cargo.toml").unwrap().contains("name = \"day24\""));
+        assert!(created(&changes, "2025/day24/benches/default.rs").is_some());
+        let registry = updated(&changes, "aoc/src/y2025.rs").unwrap();
+        assert!(registry.contains("24 => Modern::<aoc_2025_day24::Day24>::default(),"));
+
+        let changes = Scaffold::new(root(), 2023, 24).unwrap().plan().unwrap();
+        assert!(created(&changes, "2023/day24/src/bin/d24p1.rs").unwrap().contains("use day24::"));
+        let manifest = updated(&changes, "aoc/Cargo.toml").unwrap();
+        assert!(manifest.contains("aoc-2023-day24 = { package = \"aoc-2023-day24\", path = \"../2023/day24\" }\n"));
+    }
+
+    #[test]
+    fn test_refuse_existing_day() {
+        assert!(Scaffold::new(root(), 2024, 1).unwrap().plan().is_err());
+        assert!(Scaffold::new(root(), 2023, 11).unwrap().plan().is_err());
+        assert!(Scaffold::new(root(), 2022, 1).is_err());
+    }
+}
@@ -0,0 +1,402 @@
+use crate::download::DownloadConfig;
+use crate::AocError;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// What the website made of a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying which way.
+    Incorrect,
+    /// Submitted too soon after the previous answer, so it wasn't checked.
+    RateLimited,
+    /// The part has already been solved (or part 1 hasn't been yet).
+    WrongLevel,
+    /// A response that couldn't be made sense of.
+    Unknown,
+}
+impl Outcome {
+    pub fn is_wrong(&self) -> bool {
+        matches!(self, Self::TooHigh | Self::TooLow | Self::Incorrect)
+    }
+}
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Correct => write!(f, "✅ Correct"),
+            Self::TooHigh => write!(f, "❌ Too high"),
+            Self::TooLow => write!(f, "❌ Too low"),
+            Self::Incorrect => write!(f, "❌ Incorrect"),
+            Self::RateLimited => write!(f, "⏱️ Rate limited"),
+            Self::WrongLevel => write!(f, "⏭️ Not the part being solved"),
+            Self::Unknown => write!(f, "❔ Unknown response"),
+        }
+    }
+}
+
+/// The website's response to an answer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Feedback {
+    pub outcome: Outcome,
+    /// How long to wait before submitting another answer, when told.
+    pub wait: Option<Duration>,
+    /// The response, stripped of its markup.
+    pub message: String,
+}
+impl Feedback {
+    /// Make sense of the page returned after submitting an answer. Only the
+    /// `<article>` holds the response; the rest of the page is the site's
+    /// usual chrome.
+    pub fn parse(html: &str) -> Self {
+        let article = html
+            .split_once("<article")
+            .and_then(|(_, article)| Some(article.split_once('>')?.1.split_once("</article>")?.0))
+            .unwrap_or(html);
+        let message = text(article);
+        let lowercase = message.to_lowercase();
+        let outcome = if lowercase.contains("that's the right answer") {
+            Outcome::Correct
+        } else if lowercase.contains("answer is too high") {
+            Outcome::TooHigh
+        } else if lowercase.contains("answer is too low") {
+            Outcome::TooLow
+        } else if lowercase.contains("not the right answer") {
+            Outcome::Incorrect
+        } else if lowercase.contains("gave an answer too recently") {
+            Outcome::RateLimited
+        } else if lowercase.contains("solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown
+        };
+        Self { outcome, wait: wait(&lowercase), message }
+    }
+}
+
+/// Strip the markup from HTML, collapsing whitespace as a browser would.
+fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {},
+        }
+    }
+    let text = text.replace("&quot;", "\"").replace("&#39;", "'").replace("&lt;", "<").replace("&gt;", ">");
+    text.replace("&amp;", "&").split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// How long the (lowercase) response asks to wait, either as "you have 1m
+/// 30s left to wait" or as "please wait 5 minutes before trying again".
+fn wait(message: &str) -> Option<Duration> {
+    if let Some((before, _)) = message.split_once(" left to wait") {
+        let (_, left) = before.rsplit_once("you have ")?;
+        return left
+            .split_whitespace()
+            .map(|amount| {
+                let (number, unit) = amount.split_at(amount.len().checked_sub(1)?);
+                let number: u64 = number.parse().ok()?;
+                match unit {
+                    "h" => Some(number * 3_600),
+                    "m" => Some(number * 60),
+                    "s" => Some(number),
+                    _ => None,
+                }
+            })
+            .sum::<Option<u64>>()
+            .map(Duration::from_secs);
+    }
+    let (_, rest) = message.split_once("please wait ")?;
+    let mut words = rest.split_whitespace();
+    let number = match words.next()? {
+        "one" => 1,
+        number => number.parse().ok()?,
+    };
+    match words.next()?.trim_end_matches('s') {
+        "minute" => Some(Duration::from_secs(number * 60)),
+        "second" => Some(Duration::from_secs(number)),
+        _ => None,
+    }
+}
+
+/// An answer that was submitted, and what came of it.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Submission {
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_seconds: Option<u64>,
+}
+impl Submission {
+    pub fn new(day: u8, part: u8, answer: &str, feedback: &Feedback) -> Self {
+        Self {
+            timestamp: now(),
+            day,
+            part,
+            answer: answer.to_string(),
+            outcome: feedback.outcome,
+            wait_seconds: feedback.wait.map(|wait| wait.as_secs()),
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|since| since.as_secs()).unwrap_or_default()
+}
+
+/// Every answer submitted for a year's puzzles, stored next to the inputs as
+/// `input/{year}/submissions.jsonl` with one [`Submission`] per line, so that
+/// answers the website has already rejected never get submitted again.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Submissions {
+    submissions: Vec<Submission>,
+}
+impl Submissions {
+    pub fn path(year: u16) -> PathBuf {
+        PathBuf::from(format!("input/{}/submissions.jsonl", year))
+    }
+
+    /// Load the submissions for a year; a year without a submissions file
+    /// simply hasn't had any answers submitted yet.
+    pub fn load(year: u16) -> Result<Self, AocError> {
+        match fs::read_to_string(Self::path(year)) {
+            Ok(contents) => Self::from_jsonl(&contents),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(AocError::Io(err)),
+        }
+    }
+
+    pub fn from_jsonl(contents: &str) -> Result<Self, AocError> {
+        let submissions = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                serde_json::from_str(line)
+                    .map_err(|err| AocError::Submit(format!("Invalid submission on line {}: {err}", index + 1)))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { submissions })
+    }
+
+    /// Record a submission, adding it to the end of the year's submissions file.
+    pub fn append(&mut self, year: u16, submission: Submission) -> Result<(), AocError> {
+        let path = Self::path(year);
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        let line = serde_json::to_string(&submission).map_err(|err| AocError::Submit(err.to_string()))?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{line}")?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+
+    fn part(&self, day: u8, part: u8) -> impl Iterator<Item = &Submission> {
+        self.submissions.iter().filter(move |submission| submission.day == day && submission.part == part)
+    }
+
+    /// The range that a numeric answer has to be within, going by the answers
+    /// that were too low and too high.
+    pub fn bounds(&self, day: u8, part: u8) -> (Option<i128>, Option<i128>) {
+        let numbers = |outcome: Outcome| {
+            self.part(day, part).filter(move |s| s.outcome == outcome).filter_map(|s| s.answer.trim().parse().ok())
+        };
+        (numbers(Outcome::TooLow).max(), numbers(Outcome::TooHigh).min())
+    }
+
+    /// Refuse to submit an answer that could only be wrong, or while the
+    /// website has asked to wait (after any submission for the year).
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Result<(), AocError> {
+        let refuse = |reason: String| Err(AocError::Submit(reason));
+        if let Some(correct) = self.part(day, part).find(|s| s.outcome == Outcome::Correct) {
+            return match correct.answer == answer {
+                true => refuse(format!("{answer} has already been accepted")),
+                false => refuse(format!("Part {part} has already been solved, with {}", correct.answer)),
+            };
+        }
+        if let Some(wrong) = self.part(day, part).find(|s| s.answer == answer && s.outcome.is_wrong()) {
+            return refuse(format!("{answer} has already been rejected ({})", wrong.outcome));
+        }
+        if let Ok(number) = answer.trim().parse::<i128>() {
+            match self.bounds(day, part) {
+                (Some(low), _) if number <= low => return refuse(format!("{answer} is too low, as {low} was")),
+                (_, Some(high)) if number >= high => return refuse(format!("{answer} is too high, as {high} was")),
+                _ => {},
+            }
+        }
+        let until = self.submissions.iter().filter_map(|s| Some(s.timestamp + s.wait_seconds?)).max();
+        if let Some(remaining) = until.and_then(|until| until.checked_sub(now())).filter(|remaining| *remaining > 0) {
+            return refuse(format!("The website asked to wait another {remaining}s before submitting again"));
+        }
+        Ok(())
+    }
+}
+
+/// Submits answers to the Advent of Code website (or a compatible server, as
+/// configured by its base URL).
+pub struct Submitter {
+    config: DownloadConfig,
+}
+impl Submitter {
+    pub fn new(config: DownloadConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn submit(&self, year: u16, day: u8, part: u8, answer: &str) -> Result<Feedback, AocError> {
+        let session = self.config.session().ok_or_else(|| {
+            AocError::Submit("No session token configured (set AOC_SESSION or the config file)".to_string())
+        })?;
+        let url = format!("{}/{}/day/{}/answer", self.config.base_url(), year, day);
+        let response = self
+            .config
+            .agent()
+            .post(&url)
+            .set("Cookie", &format!("session={session}"))
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|err| AocError::Submit(format!("Request to {url} failed: {err}")))?;
+        Ok(Feedback::parse(&response.into_string()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn page(article: &str) -> String {
+        format!("<html><body><main><article><p>{article}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn test_feedback() {
+        let feedback = Feedback::parse(&page(
+            "That's not the right answer; your answer is too high.  If you're stuck, make sure you're using the \
+             full input data. <a href=\"/2024/day/1\">[Return to Day 1]</a> Please wait one minute before trying \
+             again. (You guessed <span style=\"white-space:nowrap;\"><code>42</code>.)</span>",
+        ));
+        assert_eq!(Outcome::TooHigh, feedback.outcome);
+        assert_eq!(Some(Duration::from_secs(60)), feedback.wait);
+        assert!(feedback.message.ends_with("(You guessed 42.)"));
+
+        let feedback = Feedback::parse(&page(
+            "You gave an answer too recently; you have to wait after submitting an answer before trying again.  \
+             You have 1m 31s left to wait. <a href=\"/2024/day/1\">[Return to Day 1]</a>",
+        ));
+        assert_eq!(Outcome::RateLimited, feedback.outcome);
+        assert_eq!(Some(Duration::from_secs(91)), feedback.wait);
+
+        let feedback =
+            Feedback::parse(&page("That's not the right answer.  Please wait 5 minutes before trying again."));
+        assert_eq!((Outcome::Incorrect, Some(Duration::from_secs(300))), (feedback.outcome, feedback.wait));
+        assert_eq!(Outcome::TooLow, Feedback::parse(&page("Your answer is too low.")).outcome);
+        assert_eq!(
+            Outcome::Correct,
+            Feedback::parse(&page("That's the right answer! You are one gold star closer.")).outcome
+        );
+        assert_eq!(
+            Outcome::WrongLevel,
+            Feedback::parse(&page("You don't seem to be solving the right level.")).outcome
+        );
+        assert_eq!(Outcome::Unknown, Feedback::parse("<html>Puzzle inputs differ by user.</html>").outcome);
+    }
+
+    fn submission(part: u8, answer: &str, outcome: Outcome) -> Submission {
+        Submission {
+            timestamp: 1_700_000_000,
+            day: 1,
+            part,
+            answer: answer.to_string(),
+            outcome,
+            wait_seconds: Some(60),
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let submissions = Submissions {
+            submissions: vec![
+                submission(1, "100", Outcome::TooHigh),
+                submission(1, "10", Outcome::TooLow),
+                submission(1, "50", Outcome::TooHigh),
+                submission(1, "abc", Outcome::Incorrect),
+                submission(2, "7", Outcome::Correct),
+            ],
+        };
+        assert_eq!((Some(10), Some(50)), submissions.bounds(1, 1));
+        assert!(submissions.check(1, 1, "42").is_ok());
+        assert!(submissions.check(1, 1, "def").is_ok());
+        assert!(submissions.check(1, 3, "42").is_ok());
+        for answer in ["abc", "10", "9", "50", "75"] {
+            assert!(matches!(submissions.check(1, 1, answer), Err(AocError::Submit(_))), "{answer}");
+        }
+        assert!(submissions.check(1, 2, "7").is_err());
+        assert!(submissions.check(1, 2, "8").is_err());
+
+        let waiting = Submissions {
+            submissions: vec![Submission {
+                timestamp: now(),
+                ..submission(1, "1", Outcome::RateLimited)
+            }],
+        };
+        assert!(waiting.check(2, 1, "42").unwrap_err().to_string().contains("wait"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let line = serde_json::to_string(&submission(1, "42", Outcome::TooHigh)).unwrap();
+        assert!(line.contains("\"outcome\":\"too-high\""));
+        let submissions = Submissions::from_jsonl(&format!("{line}\n\n{line}\n")).unwrap();
+        assert_eq!(2, submissions.submissions.len());
+        assert!(matches!(Submissions::from_jsonl("{}"), Err(AocError::Submit(_))));
+    }
+
+    #[test]
+    fn test_submit_to_stub_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(&stream);
+            let request: Vec<String> =
+                (&mut reader).lines().map_while(Result::ok).take_while(|line| !line.is_empty()).collect();
+            let length = request
+                .iter()
+                .find_map(|line| line.to_ascii_lowercase().strip_prefix("content-length: ")?.parse().ok())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let page = page("That's the right answer! You are one gold star closer to finding the chief.");
+            write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}", page.len())
+                .unwrap();
+            (request, String::from_utf8(body).unwrap())
+        });
+
+        let config: DownloadConfig =
+            toml::from_str(&format!("session = \"secret\"\nbase_url = \"http://{address}\"")).unwrap();
+        let feedback = Submitter::new(config).submit(2015, 1, 2, "1 & 2").unwrap();
+        let (request, body) = server.join().unwrap();
+
+        assert_eq!(Outcome::Correct, feedback.outcome);
+        assert_eq!("POST /2015/day/1/answer HTTP/1.1", request[0]);
+        assert!(request.iter().any(|line| line.eq_ignore_ascii_case("cookie: session=secret")));
+        assert_eq!("level=2&answer=1+%26+2", body);
+    }
+}
@@ -0,0 +1,303 @@
+use crate::AocError;
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+use serde_json::json;
+use std::collections::VecDeque;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+/// Every step of a simulation, rendered as text. Frames are produced lazily,
+/// so that playing a long simulation can start straight away.
+pub type Frames = Box<dyn Iterator<Item = String>>;
+
+/// A solution that can show how its state evolves, one step at a time, to be
+/// played back by the runner with `--visualize`.
+pub trait Visualize: Send + Sync {
+    fn frames(&self, input: &str) -> Result<Frames, AocError>;
+}
+
+/// Languages that a [`Diagram`] can be drawn in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GraphFormat {
+    /// [Graphviz](https://graphviz.org), to be rendered with `dot -Tsvg`.
+    Dot,
+    /// [Mermaid](https://mermaid.js.org), which renders on GitHub and in many editors.
+    Mermaid,
+}
+impl GraphFormat {
+    pub(crate) const NAMES: [&'static str; 2] = ["dot", "mermaid"];
+
+    pub(crate) fn parse(value: &str) -> Result<Self, String> {
+        match value.to_ascii_lowercase().as_str() {
+            "dot" => Ok(Self::Dot),
+            "mermaid" => Ok(Self::Mermaid),
+            _ => Err(format!("`{value}` is not one of: {}", Self::NAMES.join(", "))),
+        }
+    }
+}
+
+/// A solution whose input describes a graph, which can be drawn to make the
+/// structure of the puzzle easier to reason about, dumped by the runner with
+/// `--dump-graph`.
+pub trait Diagram: Send + Sync {
+    fn graph(&self, input: &str, format: GraphFormat) -> Result<String, AocError>;
+}
+
+/// The most recent frames that have been played, so that playback can step
+/// back without keeping every frame of a long simulation in memory.
+struct Playback {
+    frames: Frames,
+    history: VecDeque<String>,
+    /// How many of the earliest frames have been dropped from the history.
+    forgotten: usize,
+    current: usize,
+    exhausted: bool,
+}
+impl Playback {
+    /// How many frames can be stepped back through.
+    const HISTORY: usize = 1000;
+
+    fn new(frames: Frames) -> Self {
+        let mut playback = Self {
+            frames,
+            history: VecDeque::new(),
+            forgotten: 0,
+            current: 0,
+            exhausted: false,
+        };
+        playback.forward();
+        playback
+    }
+
+    /// Move to the next frame, or stay on the last one once there are none left.
+    fn forward(&mut self) -> bool {
+        if self.current + 1 < self.history.len() {
+            self.current += 1;
+            return true;
+        }
+        match self.frames.next() {
+            Some(frame) => {
+                if self.history.len() == Self::HISTORY {
+                    self.history.pop_front();
+                    self.forgotten += 1;
+                }
+                self.history.push_back(frame);
+                self.current = self.history.len() - 1;
+                true
+            },
+            None => {
+                self.exhausted = true;
+                false
+            },
+        }
+    }
+
+    fn back(&mut self) {
+        self.current = self.current.saturating_sub(1);
+    }
+
+    fn frame(&self) -> &str {
+        self.history.get(self.current).map(String::as_str).unwrap_or_default()
+    }
+
+    /// The number of the current frame, counting from one.
+    fn number(&self) -> usize {
+        self.forgotten + self.current + 1
+    }
+}
+
+/// Puts the terminal into raw mode on an alternate screen for as long as it's
+/// kept around, so that the original screen is restored however playback ends.
+struct Screen;
+impl Screen {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, Hide)?;
+        Ok(Self)
+    }
+}
+impl Drop for Screen {
+    fn drop(&mut self) {
+        execute!(io::stdout(), Show, LeaveAlternateScreen).ok();
+        terminal::disable_raw_mode().ok();
+    }
+}
+
+/// Animates frames in the terminal. Space pauses, the arrow keys step back
+/// and forth, `+` and `-` change the speed, and `q` quits. When standard
+/// output isn't a terminal, every frame gets printed one after the other.
+pub struct Player {
+    fps: f64,
+}
+impl Player {
+    const SPEEDS: (f64, f64) = (0.25, 240.0);
+
+    pub fn new(fps: f64) -> Self {
+        Self { fps: fps.clamp(Self::SPEEDS.0, Self::SPEEDS.1) }
+    }
+
+    pub fn play(&self, frames: Frames) -> io::Result<()> {
+        let mut stdout = io::stdout();
+        if !stdout.is_terminal() {
+            for frame in frames {
+                match writeln!(stdout, "{frame}\n") {
+                    // Whatever is reading the frames has seen enough of them.
+                    Err(err) if err.kind() == io::ErrorKind::BrokenPipe => break,
+                    written => written?,
+                }
+            }
+            return Ok(());
+        }
+
+        let _screen = Screen::enter()?;
+        let mut playback = Playback::new(frames);
+        let (mut fps, mut paused) = (self.fps, false);
+        loop {
+            Self::draw(&mut stdout, &playback, fps, paused)?;
+            let deadline = Instant::now() + Duration::from_secs_f64(1.0 / fps);
+            // Wait for the next frame to be due, unless a key changes what to show first.
+            let advance = loop {
+                let key = match paused {
+                    true => Self::key(event::read()?),
+                    false => match event::poll(deadline.saturating_duration_since(Instant::now()))? {
+                        true => Self::key(event::read()?),
+                        false => break true,
+                    },
+                };
+                let Some(key) = key else { continue };
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Right | KeyCode::Char('n') => {
+                        paused = true;
+                        playback.forward();
+                    },
+                    KeyCode::Left | KeyCode::Char('p') => {
+                        paused = true;
+                        playback.back();
+                    },
+                    KeyCode::Char('+') | KeyCode::Char('=') => fps = (fps * 2.0).min(Self::SPEEDS.1),
+                    KeyCode::Char('-') => fps = (fps / 2.0).max(Self::SPEEDS.0),
+                    _ => continue,
+                }
+                break false;
+            };
+            if advance && !playback.forward() {
+                paused = true;
+            }
+        }
+    }
+
+    fn key(event: Event) -> Option<KeyEvent> {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => Some(key),
+            _ => None,
+        }
+    }
+
+    fn draw(stdout: &mut impl Write, playback: &Playback, fps: f64, paused: bool) -> io::Result<()> {
+        queue!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
+        // Raw mode doesn't return the cursor to the start of each new line.
+        for line in playback.frame().lines() {
+            write!(stdout, "{line}\r\n")?;
+        }
+        let total = match playback.exhausted {
+            true => (playback.forgotten + playback.history.len()).to_string(),
+            false => "?".to_string(),
+        };
+        let state = match paused {
+            true => "⏸ paused",
+            false => "▶ playing",
+        };
+        write!(
+            stdout,
+            "\r\nFrame {}/{total} · {fps} fps · {state} · space: pause, ←/→: step, +/-: speed, q: quit",
+            playback.number(),
+        )?;
+        stdout.flush()
+    }
+}
+
+/// The size (in columns and rows) to record at when there's no terminal to
+/// take it from.
+pub const CAST_SIZE: (u16, u16) = (80, 24);
+
+/// The size of the current terminal, so that a recording plays back the way
+/// the frames would have been shown live.
+pub fn terminal_size() -> (u16, u16) {
+    terminal::size().unwrap_or(CAST_SIZE)
+}
+
+/// Write frames as an [asciinema](https://asciinema.org) (v2) recording for a
+/// terminal of the given size (in columns and rows), returning how many frames
+/// were written. Frames are written as they're produced, so the whole
+/// simulation never has to be kept in memory; anything that doesn't fit in the
+/// terminal gets cut off when played back.
+pub fn write_cast(
+    frames: Frames,
+    fps: f64,
+    title: &str,
+    size: (u16, u16),
+    mut writer: impl Write,
+) -> io::Result<usize> {
+    let header = json!({
+        "version": 2,
+        "width": size.0.max(1),
+        "height": size.1.max(1),
+        "title": title,
+    });
+    writeln!(writer, "{header}")?;
+    let mut count = 0;
+    for frame in frames {
+        let output = format!("\x1b[H\x1b[2J{}", frame.lines().collect::<Vec<_>>().join("\r\n"));
+        writeln!(writer, "{}", json!([count as f64 / fps, "o", output]))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames() -> Frames {
+        Box::new(["ab\ncd", "abc\nd\ne"].into_iter().map(String::from))
+    }
+
+    #[test]
+    fn test_playback() {
+        let mut playback = Playback::new(frames());
+        assert_eq!("ab\ncd", playback.frame());
+        assert!(playback.forward());
+        assert!(!playback.forward());
+        assert!(playback.exhausted);
+        playback.back();
+        assert_eq!("ab\ncd", playback.frame());
+        assert!(playback.forward());
+        assert_eq!("abc\nd\ne", playback.frame());
+    }
+
+    #[test]
+    fn test_playback_history() {
+        let mut playback = Playback::new(Box::new((1..).map(|frame| frame.to_string())));
+        while playback.number() < Playback::HISTORY + 5 {
+            playback.forward();
+        }
+        assert_eq!(Playback::HISTORY, playback.history.len());
+        (0..Playback::HISTORY + 5).for_each(|_| playback.back());
+        assert_eq!((6, "6"), (playback.number(), playback.frame()));
+    }
+
+    #[test]
+    fn test_write_cast() {
+        let mut cast = Vec::new();
+        assert_eq!(2, write_cast(frames(), 4.0, "Day 0", (3, 2), &mut cast).unwrap());
+        let lines: Vec<serde_json::Value> =
+            String::from_utf8(cast).unwrap().lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(json!({"version": 2, "width": 3, "height": 2, "title": "Day 0"}), lines[0]);
+        assert_eq!(json!([0.25, "o", "\x1b[H\x1b[2Jabc\r\nd\r\ne"]), lines[2]);
+    }
+}